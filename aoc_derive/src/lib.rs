@@ -0,0 +1,114 @@
+//! `#[derive(AocParse)]` for `advent_of_code::parse::AocParse`. The struct
+//! gets a parser built from its `#[aoc(fmt = "...")]` attribute, where each
+//! `{}` is the next field in declaration order and everything else has to
+//! match the input. See the trait for how whitespace is handled.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, Data, DeriveInput, Fields, LitStr};
+
+#[proc_macro_derive(AocParse, attributes(aoc))]
+pub fn derive_aoc_parse(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn format_attr(input: &DeriveInput) -> syn::Result<LitStr> {
+    let mut fmt = None;
+
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("aoc")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("fmt") {
+                fmt = Some(meta.value()?.parse::<LitStr>()?);
+                Ok(())
+            } else {
+                Err(meta.error("expected `fmt = \"...\"`"))
+            }
+        })?;
+    }
+
+    fmt.ok_or_else(|| {
+        syn::Error::new(Span::call_site(), "missing `#[aoc(fmt = \"...\")]`")
+    })
+}
+
+/// Parsing steps for the text between two fields, one per word
+fn literal_steps(text: &str) -> Vec<TokenStream2> {
+    text.split_whitespace()
+        .map(|word| quote!(let (input, _) = __private::literal(#word, input)?;))
+        .collect()
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let fmt = format_attr(input)?;
+
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "AocParse can only be derived for structs",
+        ));
+    };
+
+    let value = fmt.value();
+    let pieces = value.split("{}").collect::<Vec<_>>();
+    let field_count = data.fields.len();
+
+    if pieces.len() != field_count + 1 {
+        return Err(syn::Error::new_spanned(
+            &fmt,
+            format!(
+                "the format has {} `{{}}` but the struct has {field_count} \
+                 fields",
+                pieces.len() - 1
+            ),
+        ));
+    }
+
+    let names = (0..field_count)
+        .map(|i| format_ident!("field_{i}"))
+        .collect::<Vec<_>>();
+    let mut steps = literal_steps(pieces[0]);
+
+    for ((name, field), text) in
+        names.iter().zip(&data.fields).zip(&pieces[1..])
+    {
+        let ty = &field.ty;
+        steps.push(
+            quote!(let (input, #name) = __private::field::<#ty>(input)?;),
+        );
+        steps.extend(literal_steps(text));
+    }
+
+    let construct = match &data.fields {
+        Fields::Named(fields) => {
+            let idents = fields.named.iter().map(|f| &f.ident);
+            quote!(Self { #(#idents: #names),* })
+        }
+        Fields::Unnamed(_) => quote!(Self(#(#names),*)),
+        Fields::Unit => quote!(Self),
+    };
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) =
+        input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::advent_of_code::parse::AocParse
+            for #ident #ty_generics #where_clause
+        {
+            fn parse(
+                input: &str,
+            ) -> ::advent_of_code::parse::ParseResult<'_, Self> {
+                use ::advent_of_code::parse::__private;
+
+                #(#steps)*
+
+                Ok((input, #construct))
+            }
+        }
+    })
+}
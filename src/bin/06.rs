@@ -1,18 +1,27 @@
 use std::collections::HashSet;
 
-use advent_of_code::{Compass, Grid};
+use advent_of_code::{BitGrid, Compass, DirMap, Grid};
 
 advent_of_code::solution!(6);
 
-fn get_seen_positions(start: usize, grid: &Grid<char>) -> HashSet<usize> {
+/// The obstructions and the index of the guard
+fn parse(input: &str) -> (BitGrid, usize) {
+    let (grid, markers) =
+        Grid::<char>::parse_with_markers(input, "^", |_| '.').expect("🗺️");
+    let walls = BitGrid::from_grid(&grid, |c| *c == '#');
+
+    (walls, markers[&'^'][0])
+}
+
+fn get_seen_positions(start: usize, walls: &BitGrid) -> HashSet<usize> {
     let mut seen = HashSet::new();
     let mut d = Compass::N;
     let mut p = start;
 
     seen.insert(p);
 
-    while let Some(next) = grid.step_from_index(p, d) {
-        if grid.data[next] == '#' {
+    while let Some(next) = walls.step_from_index(p, d) {
+        if walls.get(next) {
             d = d.turn_right();
         } else {
             p = next;
@@ -24,42 +33,31 @@ fn get_seen_positions(start: usize, grid: &Grid<char>) -> HashSet<usize> {
 }
 
 pub fn part_one(input: &str) -> Option<usize> {
-    let grid: Grid<char> = Grid::parse_lines(input);
+    let (walls, start) = parse(input);
 
-    let start = grid
-        .data
-        .iter()
-        .enumerate()
-        .find_map(|(i, c)| (*c == '^').then_some(i))
-        .unwrap();
-
-    Some(get_seen_positions(start, &grid).len())
+    Some(get_seen_positions(start, &walls).len())
 }
 
 fn has_cycle(
     start: usize,
-    grid: &Grid<char>,
-    working_space: &mut [[u16; 4]],
-    generation: u16,
+    walls: &BitGrid,
+    visited: &mut DirMap<BitGrid>,
 ) -> bool {
     let mut d = Compass::N;
     let mut p = start;
 
-    working_space[p][d as u8 as usize] = generation;
+    visited.values_mut().for_each(BitGrid::clear);
+    visited[d].set(p, true);
 
-    while let Some(next) = grid.step_from_index(p, d) {
-        if grid.data[next] == '#' {
+    while let Some(next) = walls.step_from_index(p, d) {
+        if walls.get(next) {
             d = d.turn_right();
         } else {
             p = next;
 
-            let cell = &mut working_space[p][d as u8 as usize];
-
-            if *cell == generation {
+            if !visited[d].insert(p) {
                 return true;
             }
-
-            *cell = generation;
         }
     }
 
@@ -67,37 +65,26 @@ fn has_cycle(
 }
 
 pub fn part_two(input: &str) -> Option<usize> {
-    let mut grid: Grid<char> = Grid::parse_lines(input);
-    let start = grid
-        .data
-        .iter()
-        .enumerate()
-        .find_map(|(i, c)| (*c == '^').then_some(i))
-        .unwrap();
+    let (mut walls, start) = parse(input);
 
-    let possible_positions = get_seen_positions(start, &grid);
+    let possible_positions = get_seen_positions(start, &walls);
 
     // Originally I detected cycles with a hashmap, but that was very slow.
-    // This method uses a fixed (but large) amount of memory for all checks of
-    // cycles. We duplicate the shape of the grid with a slot for each direction
-    // the guard could be facing there.
-    //
-    // For each possible position of the new obstacle, we set a new "generation"
-    // and use that as the marker for position-direction pairs, if a traversal
-    // of the new grid encounters a slot with the current generation, it
-    // indicates a repeated vector and a cycle
-    let mut cycle_detector = vec![[0; 4]; grid.data.len()];
+    // Instead we keep one bit grid per direction the guard could be facing and
+    // mark each position-direction pair as it is visited. Reaching a pair that
+    // is already marked means the guard repeats itself and is in a cycle. The
+    // grids are packed, so clearing them for every new obstacle is cheap.
+    let mut visited =
+        DirMap::from_fn(|_| BitGrid::new(walls.width, walls.height));
 
     let result = possible_positions
         .into_iter()
-        .enumerate()
-        .filter(|(i, ob)| {
-            grid.data[*ob] = '#';
+        .filter(|ob| {
+            walls.set(*ob, true);
 
-            let result =
-                has_cycle(start, &grid, &mut cycle_detector, *i as u16 + 1);
+            let result = has_cycle(start, &walls, &mut visited);
 
-            grid.data[*ob] = '.';
+            walls.set(*ob, false);
 
             result
         })
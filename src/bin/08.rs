@@ -1,13 +1,13 @@
 use std::collections::{HashMap, HashSet};
 
-use advent_of_code::Grid;
+use advent_of_code::{Grid, Pos};
 use itertools::Itertools;
 
 advent_of_code::solution!(8);
 
 fn record_first_antinode_locations(
-    antennas: &[(i64, i64)],
-    record: &mut HashSet<(i64, i64)>,
+    antennas: &[Pos],
+    record: &mut HashSet<Pos>,
 ) {
     record.extend(
         antennas
@@ -15,26 +15,20 @@ fn record_first_antinode_locations(
             .cartesian_product(antennas)
             .filter(|(l, r)| l != r)
             .flat_map(|(l, r)| {
-                let dx = l.0 - r.0;
-                let dy = l.1 - r.1;
+                let d = *l - *r;
 
-                [(l.0 + dx, l.1 + dy), (r.0 - dx, r.1 - dy)]
+                [*l + d, *r - d]
             }),
     );
 }
 
-fn get_antennas(grid: &Grid<char>) -> HashMap<char, Vec<(i64, i64)>> {
-    let to_signed_col_row = |i| {
-        let (col, row) = grid.to_col_row(i);
-        (col as i64, row as i64)
-    };
-
+fn get_antennas(grid: &Grid<char>) -> HashMap<char, Vec<Pos>> {
     grid.data
         .iter()
         .copied()
         .enumerate()
         .filter_map(|(i, c)| (c != '.').then_some((c, i)))
-        .map(|(c, i)| (c, to_signed_col_row(i)))
+        .map(|(c, i)| (c, grid.to_pos(i)))
         .into_group_map()
 }
 
@@ -48,22 +42,15 @@ pub fn part_one(input: &str) -> Option<usize> {
         record_first_antinode_locations(&locations, &mut antinodes)
     });
 
-    let result = antinodes
-        .into_iter()
-        .filter(|(c, r)| *c >= 0 && *r >= 0)
-        .filter(|(c, r)| {
-            (*c as usize) < grid.width && (*r as usize) < grid.height
-        })
-        .count();
+    let result = antinodes.into_iter().filter(|p| grid.contains(*p)).count();
 
     Some(result)
 }
 
 fn record_all_antinode_locations(
-    antennas: &[(i64, i64)],
-    max_col: i64,
-    max_row: i64,
-    record: &mut HashSet<(i64, i64)>,
+    antennas: &[Pos],
+    grid: &Grid<char>,
+    record: &mut HashSet<Pos>,
 ) {
     record.extend(
         antennas
@@ -71,20 +58,14 @@ fn record_all_antinode_locations(
             .cartesian_product(antennas)
             .filter(|(l, r)| l != r)
             .flat_map(|(left, right)| {
-                let dx = left.0 - right.0;
-                let dy = left.1 - right.1;
+                let d = *left - *right;
 
-                [(left, (dx, dy)), (left, (-dx, -dy))]
+                [(*left, d), (*left, -d)]
             })
             .flat_map(|(left, d)| {
                 (0..)
-                    .map(move |i| (left.0 + d.0 * i, left.1 + d.1 * i))
-                    .take_while(|(col, row)| {
-                        *col >= 0
-                            && *row >= 0
-                            && *col <= max_col
-                            && *row <= max_row
-                    })
+                    .map(move |i| left + d * i)
+                    .take_while(|p| grid.contains(*p))
             }),
     );
 }
@@ -96,12 +77,7 @@ pub fn part_two(input: &str) -> Option<usize> {
     let mut antinodes = HashSet::new();
 
     antennas.into_values().for_each(|locations| {
-        record_all_antinode_locations(
-            &locations,
-            grid.width as i64 - 1,
-            grid.height as i64 - 1,
-            &mut antinodes,
-        )
+        record_all_antinode_locations(&locations, &grid, &mut antinodes)
     });
 
     Some(antinodes.len())
@@ -1,29 +1,22 @@
-use std::collections::HashSet;
-
-use advent_of_code::Grid;
+use advent_of_code::{search, Grid};
 
 advent_of_code::solution!(10);
 
+/// Every trail climbs one step per move, so all trails to a peak are
+/// shortest paths and the tied-path search finds all of them.
 fn score_trail_head(grid: &Grid<u8>, start: usize) -> (usize, usize) {
-    let mut to_search = vec![(start, 1)];
-    let mut total = 0;
-    let mut reached = HashSet::new();
-
-    while let Some((curr, needs)) = to_search.pop() {
-        if needs == 10 {
-            total += 1;
-            reached.insert(curr);
-            continue;
-        }
-
-        to_search.extend(
-            grid.neighbors(curr)
-                .filter(|(_, n)| grid.data[*n] == needs)
-                .map(|(_, n)| (n, needs + 1)),
-        );
-    }
-
-    (reached.len(), total)
+    let paths = search::all_shortest_paths(
+        [start],
+        |curr| {
+            let needs = grid.data[*curr] + 1;
+            grid.neighbors(*curr)
+                .filter(move |(_, n)| grid.data[*n] == needs)
+                .map(|(_, n)| (n, 1))
+        },
+        |curr| grid.data[*curr] == 9,
+    );
+
+    paths.map_or((0, 0), |paths| (paths.goals.len(), paths.count_paths()))
 }
 
 pub fn part_one(input: &str) -> Option<usize> {
@@ -1,14 +1,12 @@
-use std::collections::HashMap;
-
-use advent_of_code::ws;
-use itertools::Itertools;
-use nom::{character::complete::u64, multi::many1, IResult};
+use advent_of_code::{
+    memo::Memo,
+    parse::{parse_all, unsigned_list},
+};
 
 advent_of_code::solution!(11);
 
 fn parse_input(line: &str) -> Vec<u64> {
-    let result: IResult<_, _> = many1(ws(u64))(line);
-    result.expect("🐸").1
+    parse_all(unsigned_list(), line).expect("🐸")
 }
 
 pub enum Blink {
@@ -63,57 +61,25 @@ impl Problem {
 }
 
 fn blink_fast(stones: Vec<u64>, times: usize) -> u64 {
-    let mut cache = HashMap::new();
+    let mut memo = Memo::new();
 
-    let mut to_solve = stones
+    stones
         .into_iter()
-        .map(|s| Problem { s, times })
-        .collect_vec();
-
-    let original = to_solve.clone();
-
-    while let Some(problem) = to_solve.pop() {
-        match cache.get(&problem).copied() {
-            Some(_) => {}
-            None if problem.times == 0 => {
-                cache.insert(problem, 1);
-            }
-            None => match problem.blink() {
-                (pl, Some(pr)) => {
-                    match (cache.get(&pl).copied(), cache.get(&pr).copied()) {
-                        (Some(al), Some(ar)) => {
-                            cache.insert(problem, al + ar);
-                        }
-                        (Some(_), None) => {
-                            to_solve.extend_from_slice(&[problem, pr]);
-                        }
-                        (None, Some(_)) => {
-                            to_solve.extend_from_slice(&[problem, pl]);
-                        }
-                        _ => {
-                            to_solve.extend_from_slice(&[problem, pl, pr]);
-                        }
-                    }
-                }
-                (p, None) => match cache.get(&p).copied() {
-                    Some(ans) => {
-                        cache.insert(problem, ans);
-                    }
-                    None => {
-                        to_solve.extend_from_slice(&[problem, p]);
+        .map(|s| {
+            memo.solve(
+                Problem { s, times },
+                |problem| match problem.times {
+                    0 => vec![],
+                    _ => {
+                        let (l, r) = problem.blink();
+                        [Some(l), r].into_iter().flatten().collect()
                     }
                 },
-            },
-        }
-    }
-
-    original
-        .iter()
-        .map(|s| {
-            let Some(ans) = cache.get(s).copied() else {
-                unreachable!("Answers are present")
-            };
-            ans
+                |problem, answers| match problem.times {
+                    0 => 1,
+                    _ => answers.iter().sum(),
+                },
+            )
         })
         .sum()
 }
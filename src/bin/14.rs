@@ -1,79 +1,53 @@
 advent_of_code::solution!(14);
-use advent_of_code::Grid;
-use itertools::Itertools;
-use nom::{
-    bytes::complete::tag,
-    character::complete::{char, i64},
-    combinator::map,
-    sequence::{preceded, separated_pair},
-    IResult,
+use advent_of_code::{
+    math,
+    parse::AocParse,
+    visualize::{self, FrameSink, Recorder},
+    Grid, Pos, Topology, Vec2,
 };
 
-fn parse_line(line: &str) -> Robot {
-    let result: IResult<_, _> = map(
-        separated_pair(
-            preceded(tag("p="), separated_pair(i64, char(','), i64)),
-            tag(" v="),
-            separated_pair(i64, char(','), i64),
-        ),
-        |(p, v)| Robot::from_parsed(p, v),
-    )(line);
-
-    result.expect("😍").1
+fn parse_robots(input: &str) -> Vec<Robot> {
+    Robot::parse_lines(input).expect("😍")
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, AocParse)]
+#[aoc(fmt = "p={} v={}")]
 struct Robot {
-    px: i64,
-    py: i64,
-    vx: i64,
-    vy: i64,
+    p: Pos,
+    v: Vec2,
 }
 
-impl Robot {
-    fn from_parsed(p: (i64, i64), v: (i64, i64)) -> Self {
-        Robot {
-            px: p.0,
-            py: p.1,
-            vx: v.0,
-            vy: v.1,
-        }
-    }
-
-    fn after_time(self, t: i64, size: (i64, i64)) -> Self {
-        let (sx, sy) = size;
-        let Robot {
-            mut px,
-            mut py,
-            vx,
-            vy,
-        } = self;
+/// The floor the robots walk on, wrapping around at the edges
+fn floor((width, height): (usize, usize)) -> Grid<char> {
+    Grid::new(vec![' '; width * height], width).with_topology(Topology::TORUS)
+}
 
-        px = (px + vx * t).rem_euclid(sx);
-        py = (py + vy * t).rem_euclid(sy);
+impl Robot {
+    fn after_time(self, t: i64, floor: &Grid<char>) -> Self {
+        let p = floor.translate(self.p, self.v * t).expect("🍩");
 
-        Robot { px, py, vx, vy }
+        Robot { p, ..self }
     }
 
-    fn quadrant(self, size: (i64, i64)) -> Option<i64> {
-        let (sx, sy) = size;
-        let Robot { px, py, .. } = self;
-        let bx = sx / 2;
-        let by = sy / 2;
+    fn quadrant(self, floor: &Grid<char>) -> Option<i64> {
+        let Pos { x, y } = self.p;
+        let bx = floor.width as i64 / 2;
+        let by = floor.height as i64 / 2;
 
-        let qx = (px != bx).then_some(px / (bx + 1))?;
-        let qy = (py != by).then_some(py / (by + 1))?;
+        let qx = (x != bx).then_some(x / (bx + 1))?;
+        let qy = (y != by).then_some(y / (by + 1))?;
 
         Some(2 * qy + qx)
     }
 }
 
-fn p1_sized(input: &str, size: (i64, i64)) -> usize {
-    input
-        .lines()
-        .map(parse_line)
-        .map(|r| r.after_time(100, size))
-        .filter_map(|r| r.quadrant(size))
+fn p1_sized(input: &str, size: (usize, usize)) -> usize {
+    let floor = floor(size);
+
+    parse_robots(input)
+        .into_iter()
+        .map(|r| r.after_time(100, &floor))
+        .filter_map(|r| r.quadrant(&floor))
         .fold([0; 4], |mut acc, curr| {
             acc[curr as usize] += 1;
             acc
@@ -88,43 +62,56 @@ pub fn part_one(input: &str) -> Option<usize> {
     Some(result)
 }
 
-fn entropy(robots: &[Robot], size: (i64, i64)) -> f64 {
-    let buckets_w = size.0 / 5 + 1;
-    let bucket_count = buckets_w * (size.1 / 5 + 1);
+fn entropy(robots: &[Robot], floor: &Grid<char>) -> f64 {
+    let buckets_w = floor.width as i64 / 5 + 1;
+    let bucket_count = buckets_w * (floor.height as i64 / 5 + 1);
 
-    let buckets = robots.iter().map(|r| r.px / 5 + r.py / 5 * buckets_w).fold(
-        vec![0.; bucket_count as usize],
-        |mut acc, curr| {
+    let buckets = robots
+        .iter()
+        .map(|r| r.p.x / 5 + r.p.y / 5 * buckets_w)
+        .fold(vec![0.; bucket_count as usize], |mut acc, curr| {
             acc[curr as usize] += 1.;
             acc
-        },
-    );
+        });
 
     buckets.into_iter().map(|c: f64| (c - 1.).abs()).sum()
 }
 
-/// For the vibes
-#[allow(dead_code)]
-fn render(robots: &[Robot], size: (i64, i64)) {
-    let mut grid =
-        Grid::new(vec![' '; (size.0 * size.1) as usize], size.0 as usize);
+/// Push the floor with a `#` on every robot
+fn push_frame(
+    robots: &[Robot],
+    floor: &Grid<char>,
+    frames: &mut impl FrameSink<char>,
+) {
+    if !frames.is_recording() {
+        return;
+    }
 
+    let mut grid = floor.clone();
     robots.iter().for_each(|r| {
-        let i = grid.to_index(r.px as usize, r.py as usize);
-        grid.data[i] = '#';
+        *grid.get_mut(r.p).unwrap() = '#';
     });
 
-    grid.print();
+    frames.push(&grid);
 }
 
-pub fn part_two(input: &str) -> Option<i64> {
-    let size = (101, 103);
-    let mut robots = input.lines().map(parse_line).collect_vec();
-    // let mut originals = robots.clone();
+fn p2_run(
+    input: &str,
+    size: (usize, usize),
+    frames: &mut impl FrameSink<char>,
+) -> usize {
+    let floor = floor(size);
+    let mut robots = parse_robots(input);
 
-    let (max_i, _) = (1..10_000).fold((0, 0.), |(max_i, max_ent), i| {
-        robots.iter_mut().for_each(|r| *r = r.after_time(1, size));
-        let ent = entropy(&robots, size);
+    // every robot is back where it started after this many steps, so every
+    // picture they make shows up within one period
+    let period = math::lcm(size.0, size.1);
+
+    let (max_i, _) = (1..period).fold((0, 0.), |(max_i, max_ent), i| {
+        robots.iter_mut().for_each(|r| *r = r.after_time(1, &floor));
+        push_frame(&robots, &floor, frames);
+
+        let ent = entropy(&robots, &floor);
         if ent > max_ent {
             (i, ent)
         } else {
@@ -132,16 +119,28 @@ pub fn part_two(input: &str) -> Option<i64> {
         }
     });
 
-    // originals
-    //     .iter_mut()
-    //     .for_each(|r| *r = r.after_time(max_i, size));
-    // render(&originals, size);
+    max_i
+}
 
-    Some(max_i)
+fn recorder() -> Recorder<char> {
+    Recorder::new(|c: &char| match c {
+        '#' => [40, 200, 60],
+        _ => [0, 0, 0],
+    })
+}
+
+pub fn part_two(input: &str) -> Option<usize> {
+    let result =
+        visualize::record(recorder, |frames| p2_run(input, (101, 103), frames));
+
+    Some(result.expect("🎄"))
 }
 
 #[cfg(test)]
 mod tests {
+    use advent_of_code::generate::Robots;
+    use rand::{rngs::StdRng, SeedableRng};
+
     use super::*;
 
     #[test]
@@ -152,4 +151,21 @@ mod tests {
         );
         assert_eq!(result, 12);
     }
+
+    #[test]
+    fn test_record_part_two() {
+        let mut recorder = recorder();
+
+        let input = advent_of_code::template::read_file("examples", DAY);
+        p2_run(&input, (11, 7), &mut recorder);
+        // the robots repeat after lcm(11, 7) steps
+        assert_eq!(recorder.len(), 76);
+    }
+
+    #[test]
+    fn test_generated() {
+        let mut rng = StdRng::seed_from_u64(14);
+        let (input, at) = Robots::default().generate_planted(200, &mut rng);
+        assert_eq!(part_two(&input), Some(at));
+    }
 }
@@ -1,7 +1,9 @@
 use std::collections::HashMap;
 
-use advent_of_code::{Compass, Grid};
-use itertools::Itertools;
+use advent_of_code::{
+    visualize::{self, FrameSink, Recorder},
+    Compass, Grid,
+};
 
 advent_of_code::solution!(15);
 
@@ -15,14 +17,16 @@ enum Obj {
     None,
 }
 
-impl From<char> for Obj {
-    fn from(value: char) -> Self {
+impl TryFrom<char> for Obj {
+    type Error = char;
+
+    fn try_from(value: char) -> Result<Self, char> {
         match value {
-            '@' => Obj::Robot,
-            '.' => Obj::None,
-            'O' => Obj::Box,
-            '#' => Obj::Wall,
-            _ => unreachable!(),
+            '@' => Ok(Obj::Robot),
+            '.' => Ok(Obj::None),
+            'O' => Ok(Obj::Box),
+            '#' => Ok(Obj::Wall),
+            c => Err(c),
         }
     }
 }
@@ -40,16 +44,6 @@ impl From<Obj> for char {
     }
 }
 
-fn parse_instr(i: u8) -> Option<Compass> {
-    match i {
-        b'>' => Some(Compass::E),
-        b'<' => Some(Compass::W),
-        b'^' => Some(Compass::N),
-        b'v' => Some(Compass::S),
-        _ => None,
-    }
-}
-
 fn p1_step(robot: usize, dir: Compass, grid: &mut Grid<Obj>) -> usize {
     let mut first_box_opt = None;
     let mut first_gap = None;
@@ -82,7 +76,7 @@ fn p1_step(robot: usize, dir: Compass, grid: &mut Grid<Obj>) -> usize {
 pub fn part_one(input: &str) -> Option<usize> {
     let (map, inst) = input.split_once("\n\n").unwrap();
 
-    let mut grid: Grid<Obj> = Grid::parse_lines(map);
+    let mut grid: Grid<Obj> = Grid::try_parse_lines(map).expect("🧱");
 
     let robot = grid
         .data
@@ -91,8 +85,8 @@ pub fn part_one(input: &str) -> Option<usize> {
         .find_map(|(i, o)| (*o == Obj::Robot).then_some(i))
         .unwrap();
 
-    inst.bytes()
-        .filter_map(parse_instr)
+    inst.chars()
+        .filter_map(Compass::from_arrow)
         // .fold(robot, |robot, dir| {
         //     let r = p1_step(robot, dir, &mut grid);
         //     println!("\n{dir}\n");
@@ -105,7 +99,7 @@ pub fn part_one(input: &str) -> Option<usize> {
         .data
         .iter()
         .enumerate()
-        .filter(|&(_, o)| (*o == Obj::Box))
+        .filter(|&(_, o)| *o == Obj::Box)
         .map(|(i, _)| {
             let (c, r) = grid.to_col_row(i);
             100 * r + c
@@ -159,15 +153,11 @@ fn p2_step(robot: usize, dir: Compass, grid: &mut Grid<Obj>) -> usize {
     grid.step_from_index(robot, dir).unwrap()
 }
 
-pub fn part_two(input: &str) -> Option<usize> {
+fn p2_run(input: &str, frames: &mut impl FrameSink<Obj>) -> usize {
     let (map, inst) = input.split_once("\n\n").unwrap();
 
-    let grid: Grid<Obj> = Grid::parse_lines(map);
-    let new_width = grid.width * 2;
-    let mut grid = Grid::new(
-        grid.data.into_iter().flat_map(Obj::expand).collect_vec(),
-        new_width,
-    );
+    let grid: Grid<Obj> = Grid::try_parse_lines(map).expect("🧱");
+    let mut grid = grid.scale_x(2, Obj::expand);
 
     let robot = grid
         .data
@@ -178,28 +168,46 @@ pub fn part_two(input: &str) -> Option<usize> {
 
     grid.data[robot] = Obj::None;
 
-    inst.bytes()
-        .filter_map(parse_instr)
-        // .fold(robot, |robot, dir| {
-        //     let r = p2_step(robot, dir, &mut grid);
-        //     println!("\n{dir}\n");
-        //     grid.print();
-        //     r
-        // });
-        .fold(robot, |robot, dir| p2_step(robot, dir, &mut grid));
+    inst.chars()
+        .filter_map(Compass::from_arrow)
+        .fold(robot, |robot, dir| {
+            let robot = p2_step(robot, dir, &mut grid);
+
+            grid.data[robot] = Obj::Robot;
+            frames.push(&grid);
+            grid.data[robot] = Obj::None;
+
+            robot
+        });
 
     let result = grid
         .data
         .iter()
         .enumerate()
-        .filter(|&(_, o)| (*o == Obj::BoxL))
+        .filter(|&(_, o)| *o == Obj::BoxL)
         .map(|(i, _)| {
             let (c, r) = grid.to_col_row(i);
             100 * r + c
         })
         .sum();
 
-    Some(result)
+    result
+}
+
+fn recorder() -> Recorder<Obj> {
+    Recorder::new(|o: &Obj| match o {
+        Obj::Wall => [90, 90, 90],
+        Obj::BoxL | Obj::BoxR => [200, 140, 40],
+        Obj::Robot => [230, 30, 30],
+        _ => [0, 0, 0],
+    })
+    .every(10)
+}
+
+pub fn part_two(input: &str) -> Option<usize> {
+    let result = visualize::record(recorder, |frames| p2_run(input, frames));
+
+    Some(result.expect("📼"))
 }
 
 #[cfg(test)]
@@ -227,4 +235,14 @@ mod tests {
             part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(9021));
     }
+
+    #[test]
+    fn test_record_part_two() {
+        let mut recorder = recorder();
+
+        let input = advent_of_code::template::read_file("examples", DAY);
+        assert_eq!(p2_run(&input, &mut recorder), 9021);
+        // one frame out of every 10 of the 700 moves
+        assert_eq!(recorder.len(), 70);
+    }
 }
@@ -1,196 +1,66 @@
-use std::{
-    collections::{HashMap, HashSet},
-    hash::Hash,
-};
-
-use advent_of_code::{Compass, Grid};
-use priority_queue::PriorityQueue;
+use advent_of_code::{search, Compass, Grid, Turn};
+use itertools::Itertools;
+use strum::IntoEnumIterator;
 
 advent_of_code::solution!(16);
 
-pub fn part_one(input: &str) -> Option<i64> {
-    use Compass as D;
-    let grid: Grid<char> = Grid::parse_lines(input);
-
-    let mut start = 0;
-    let mut end = 0;
-
-    grid.data.iter().enumerate().for_each(|(i, v)| {
-        if *v == 'S' {
-            start = i;
-        } else if *v == 'E' {
-            end = i;
-        }
-    });
-
-    let mut pq = PriorityQueue::new();
-    pq.push((start, D::E), 0);
-    let mut seen = HashSet::new();
-    seen.insert((start, D::E));
-
-    while let Some(((curr_p, curr_d), dist)) = pq.pop() {
-        if curr_p == end {
-            return Some(-dist);
-        }
-
-        let dir_l = curr_d.turn_left();
-        let dir_r = curr_d.turn_right();
-
-        let next_l = grid.step_from_index(curr_p, dir_l).unwrap();
-        if grid.data[next_l] != '#' && seen.insert((curr_p, dir_l)) {
-            pq.push((curr_p, dir_l), dist - 1000);
-        }
-
-        let next_r = grid.step_from_index(curr_p, dir_r).unwrap();
-        if grid.data[next_r] != '#' && seen.insert((curr_p, dir_r)) {
-            pq.push((curr_p, dir_r), dist - 1000);
-        }
-
-        let next_p = grid.step_from_index(curr_p, curr_d).unwrap();
-        if grid.data[next_p] != '#' && seen.insert((next_p, curr_d)) {
-            pq.push((next_p, curr_d), dist - 1);
-        }
-    }
+type State = (usize, Compass);
 
-    None
-}
+fn parse(input: &str) -> (Grid<char>, usize, usize) {
+    let (grid, markers) =
+        Grid::parse_with_markers(input, "SE", |_| '.').expect("🦌");
 
-#[derive(Debug)]
-struct Path {
-    seen: HashSet<(usize, Compass)>,
-    curr: (usize, Compass),
+    (grid, markers[&'S'][0], markers[&'E'][0])
 }
 
-impl Path {
-    fn new(
-        mut seen: HashSet<(usize, Compass)>,
-        curr: (usize, Compass),
-    ) -> Self {
-        seen.insert(curr);
-        Path { seen, curr }
-    }
+/// Turning in place costs 1000 per quarter turn and moving forward costs 1.
+/// Turns are only worth it when they face an open tile.
+fn moves<'a>(
+    grid: &'a Grid<char>,
+    &(p, d): &State,
+) -> impl Iterator<Item = (State, usize)> + 'a {
+    Compass::iter().filter_map(move |dir| {
+        let next = grid.step_from_index(p, dir)?;
+        let (state, cost) = match d.turn_to(dir) {
+            Turn::Straight => ((next, d), 1),
+            Turn::Back => return None,
+            turn => ((p, dir), 1000 * turn.quarter_turns()),
+        };
+
+        (grid.data[next] != '#').then_some((state, cost))
+    })
 }
 
-impl Hash for Path {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        self.curr.hash(state);
-    }
-}
+pub fn part_one(input: &str) -> Option<usize> {
+    let (grid, start, end) = parse(input);
 
-impl PartialEq for Path {
-    fn eq(&self, _: &Self) -> bool {
-        false
-    }
+    search::dijkstra(
+        [(start, Compass::E)],
+        |s| moves(&grid, s),
+        |(p, _)| *p == end,
+    )
+    .map(|(_, dist)| dist)
 }
 
-impl Eq for Path {}
-
 pub fn part_two(input: &str) -> Option<usize> {
-    use Compass as D;
-    let grid: Grid<char> = Grid::parse_lines(input);
-    let mut fastest = HashMap::new();
-
-    let mut start = 0;
-    let mut end = 0;
-
-    grid.data.iter().enumerate().for_each(|(i, v)| {
-        if *v == 'S' {
-            start = i;
-        } else if *v == 'E' {
-            end = i;
-        }
-    });
-
-    let mut pq = PriorityQueue::new();
-    pq.push(Path::new(HashSet::new(), (start, D::E)), 0);
-
-    let mut next_list = Vec::with_capacity(3);
-
-    let mut shortest_opt = None;
-    let mut nodes_in_any_path = HashSet::new();
-    let mut used_paths = Vec::new();
-
-    while let Some((path, dist)) = pq.pop() {
-        let Path {
-            mut seen,
-            curr: (curr_p, curr_d),
-        } = path;
-
-        if curr_p == end {
-            if let Some(shortest) = shortest_opt {
-                if dist != shortest {
-                    break;
-                }
-            } else {
-                shortest_opt = Some(dist);
-            }
-
-            // let mut g = grid.clone();
-            // seen.iter().for_each(|(i, _)| g.data[*i] = 'O');
-            // g.print();
-
-            nodes_in_any_path.extend(seen.into_iter().map(|(i, _)| i));
-            continue;
-        }
-
-        if let Some(fastest_d) = fastest.get(&(curr_p, curr_d)) {
-            if dist != *fastest_d {
-                continue;
-            }
-        } else {
-            fastest.insert((curr_p, curr_d), dist);
-        }
-
-        debug_assert!(next_list.is_empty());
-
-        let dir_l = curr_d.turn_left();
-        let dir_r = curr_d.turn_right();
-
-        let next_l = grid.step_from_index(curr_p, dir_l).unwrap();
-        if grid.data[next_l] != '#' && !seen.contains(&(curr_p, dir_l)) {
-            next_list.push(((curr_p, dir_l), dist - 1000));
-        }
-
-        let next_r = grid.step_from_index(curr_p, dir_r).unwrap();
-        if grid.data[next_r] != '#' && !seen.contains(&(curr_p, dir_r)) {
-            next_list.push(((curr_p, dir_r), dist - 1000));
-        }
-
-        let next_p = grid.step_from_index(curr_p, curr_d).unwrap();
-        if grid.data[next_p] != '#' && !seen.contains(&(next_p, curr_d)) {
-            next_list.push(((next_p, curr_d), dist - 1));
-        }
-
-        if next_list.is_empty() {
-            seen.clear();
-            used_paths.push(seen);
-        } else {
-            while next_list.len() > 1 {
-                let (curr, dist) = next_list.pop().unwrap();
-
-                let next_seen = used_paths
-                    .pop()
-                    .map(|mut used| {
-                        used.clone_from(&seen);
-                        used
-                    })
-                    .unwrap_or_else(|| seen.clone());
-
-                let path = Path::new(next_seen, curr);
-                pq.push(path, dist);
-            }
-
-            let (curr, dist) = next_list.pop().unwrap();
-            let path = Path::new(seen, curr);
-            pq.push(path, dist);
-        }
-    }
+    let (grid, start, end) = parse(input);
+
+    let paths = search::all_shortest_paths(
+        [(start, Compass::E)],
+        |s| moves(&grid, s),
+        |(p, _)| *p == end,
+    )?;
 
-    Some(nodes_in_any_path.len())
+    let result = paths.on_any_path().into_iter().map(|(p, _)| p).unique();
+
+    Some(result.count())
 }
 
 #[cfg(test)]
 mod tests {
+    use advent_of_code::generate::generator_for;
+    use rand::{rngs::StdRng, SeedableRng};
+
     use super::*;
 
     #[test]
@@ -206,4 +76,14 @@ mod tests {
             part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(45));
     }
+
+    #[test]
+    fn test_generated() {
+        let mut rng = StdRng::seed_from_u64(16);
+        let input = generator_for(DAY).unwrap().generate(41, &mut rng);
+
+        // the corners are 76 steps apart, with at least one turn in between
+        assert!(part_one(&input).is_some_and(|score| score >= 1076));
+        assert!(part_two(&input).is_some_and(|tiles| tiles >= 77));
+    }
 }
@@ -1,11 +1,13 @@
-use advent_of_code::ws;
+use advent_of_code::{
+    parse::{labeled, parse_all},
+    ws,
+};
 use itertools::Itertools;
 use nom::{
-    bytes::complete::tag,
-    character::complete::{anychar, char, i64, u8},
+    character::complete::{char, i64, u8},
     combinator::map,
     multi::separated_list1,
-    sequence::{preceded, tuple},
+    sequence::tuple,
     IResult,
 };
 use strum::FromRepr;
@@ -32,25 +34,23 @@ struct State<'a> {
     i: u8,
 }
 
-fn parse_register(input: &str) -> IResult<&'_ str, i64> {
-    preceded(tuple((tag("Register "), anychar, tag(": "))), i64)(input)
-}
-
 fn parse_registers(input: &str) -> IResult<&'_ str, [i64; 3]> {
     map(
-        tuple((ws(parse_register), ws(parse_register), ws(parse_register))),
+        tuple((
+            ws(labeled("Register A", i64)),
+            ws(labeled("Register B", i64)),
+            ws(labeled("Register C", i64)),
+        )),
         |(a, b, c)| [a, b, c],
     )(input)
 }
 
 fn parse_program(input: &str) -> IResult<&'_ str, Vec<u8>> {
-    preceded(tag("Program: "), separated_list1(char(','), u8))(input)
+    labeled("Program", separated_list1(char(','), u8))(input)
 }
 
 fn parse_input(input: &str) -> ([i64; 3], Vec<u8>) {
-    let result: IResult<_, _> = tuple((parse_registers, parse_program))(input);
-
-    result.expect("👊").1
+    parse_all(tuple((parse_registers, parse_program)), input).expect("👊")
 }
 
 impl Op {
@@ -149,7 +149,7 @@ pub fn part_one(input: &str) -> Option<String> {
     let mut output = vec![];
 
     while let Ok(output_opt) = state.step() {
-        output.extend(output_opt.into_iter());
+        output.extend(output_opt);
     }
 
     Some(output.into_iter().map(|v| v.to_string()).join(","))
@@ -1,44 +1,29 @@
-use std::collections::{HashSet, VecDeque};
-
-use advent_of_code::{ws, Grid};
-use nom::{
-    character::complete::{char, u64},
-    combinator::map,
-    multi::many1,
-    sequence::separated_pair,
-    IResult,
+use advent_of_code::{
+    parse::{coord, parse_all},
+    search, ws, BitGrid,
 };
+use nom::{combinator::map, multi::many1};
 
 advent_of_code::solution!(18);
 
 fn parse_input(input: &str) -> Vec<(usize, usize)> {
-    let result: IResult<_, _> =
-        many1(ws(map(separated_pair(u64, char(','), u64), |(c, r)| {
-            (c as usize, r as usize)
-        })))(input);
+    let bytes = many1(ws(map(coord(), |p| (p.x as usize, p.y as usize))));
 
-    result.expect("🤫").1
+    parse_all(bytes, input).expect("🤫")
 }
 
-fn bfs(grid: &Grid<char>, start: usize, end: usize) -> Option<usize> {
-    let mut queue = [(start, 0)].into_iter().collect::<VecDeque<_>>();
-    let mut seen = HashSet::new();
-
-    while let Some((curr, dist)) = queue.pop_front() {
-        if curr == end {
-            return Some(dist);
-        }
-
-        queue.extend(
-            grid.neighbors(curr)
-                .filter_map(|(_, n)| {
-                    (grid.data[n] != '#').then_some((n, dist + 1))
-                })
-                .filter(|(n, _)| seen.insert(*n)),
-        );
-    }
-
-    None
+fn bfs(walls: &BitGrid, start: usize, end: usize) -> Option<usize> {
+    search::bfs(
+        start,
+        |curr| {
+            walls
+                .neighbors(*curr)
+                .filter(|(_, n)| !walls.get(*n))
+                .map(|(_, n)| n)
+        },
+        |curr| *curr == end,
+    )
+    .map(|(_, dist)| dist)
 }
 
 fn p1_sized(
@@ -46,46 +31,46 @@ fn p1_sized(
     size: (usize, usize),
     fall_count: usize,
 ) -> Option<usize> {
-    let mut grid: Grid<char> = Grid::new(vec!['.'; size.0 * size.1], size.0);
+    let mut walls = BitGrid::new(size.0, size.1);
     let bytes = parse_input(input);
 
     bytes.into_iter().take(fall_count).for_each(|(c, r)| {
-        let i = grid.to_index(c, r);
-        grid.data[i] = '#';
+        let i = walls.to_index(c, r);
+        walls.set(i, true);
     });
 
-    bfs(&grid, 0, grid.data.len() - 1)
+    bfs(&walls, 0, size.0 * size.1 - 1)
 }
 
 pub fn part_one(input: &str) -> Option<usize> {
     p1_sized(input, (71, 71), 1024)
 }
 
-fn binary_search(grid: &mut Grid<char>, bytes: &[(usize, usize)]) -> usize {
+fn binary_search(walls: &mut BitGrid, bytes: &[(usize, usize)]) -> usize {
     let mut left = 0;
     let mut right = bytes.len();
     let start = 0;
-    let end = grid.data.len() - 1;
+    let end = walls.width * walls.height - 1;
 
     while right - left > 1 {
         let middle = (right + left) / 2;
 
         bytes.iter().copied().take(middle).for_each(|(c, r)| {
-            let i = grid.to_index(c, r);
-            debug_assert!(grid.data[i] == '.');
-            grid.data[i] = '#';
+            let i = walls.to_index(c, r);
+            debug_assert!(!walls.get(i));
+            walls.set(i, true);
         });
 
-        if bfs(grid, start, end).is_some() {
+        if bfs(walls, start, end).is_some() {
             left = middle;
         } else {
             right = middle;
         }
 
         bytes.iter().copied().take(middle).for_each(|(c, r)| {
-            let i = grid.to_index(c, r);
-            debug_assert!(grid.data[i] == '#');
-            grid.data[i] = '.';
+            let i = walls.to_index(c, r);
+            debug_assert!(walls.get(i));
+            walls.set(i, false);
         });
     }
 
@@ -93,17 +78,17 @@ fn binary_search(grid: &mut Grid<char>, bytes: &[(usize, usize)]) -> usize {
 }
 
 fn p2_sized(input: &str, size: (usize, usize), start_fall: usize) -> String {
-    let mut grid: Grid<char> = Grid::new(vec!['.'; size.0 * size.1], size.0);
+    let mut walls = BitGrid::new(size.0, size.1);
     let bytes = parse_input(input);
 
     let mut fall_count = start_fall + 1;
 
     bytes.iter().copied().take(fall_count).for_each(|(c, r)| {
-        let i = grid.to_index(c, r);
-        grid.data[i] = '#';
+        let i = walls.to_index(c, r);
+        walls.set(i, true);
     });
 
-    fall_count += binary_search(&mut grid, &bytes[fall_count..]);
+    fall_count += binary_search(&mut walls, &bytes[fall_count..]);
 
     format!("{},{}", bytes[fall_count].0, bytes[fall_count].1)
 }
@@ -114,6 +99,9 @@ pub fn part_two(input: &str) -> Option<String> {
 
 #[cfg(test)]
 mod tests {
+    use advent_of_code::generate::generator_for;
+    use rand::{rngs::StdRng, SeedableRng};
+
     use super::*;
 
     #[test]
@@ -135,4 +123,17 @@ mod tests {
         );
         assert_eq!(result, "6,1".to_owned());
     }
+
+    #[test]
+    fn test_generated() {
+        let mut rng = StdRng::seed_from_u64(18);
+        let input = generator_for(DAY).unwrap().generate(3000, &mut rng);
+        assert!(part_one(&input).is_some());
+
+        // the path is open right until the answer falls
+        let answer = part_two(&input).unwrap();
+        let fallen = input.lines().position(|l| l == answer).unwrap();
+        assert!(p1_sized(&input, (71, 71), fallen).is_some());
+        assert!(p1_sized(&input, (71, 71), fallen + 1).is_none());
+    }
 }
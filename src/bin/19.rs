@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 
-use advent_of_code::ws;
+use advent_of_code::{memo::Memo, ws};
 use itertools::Itertools;
 use nom::{
     character::complete::{alpha1, char},
@@ -33,36 +33,33 @@ pub fn part_one(input: &str) -> Option<usize> {
     Some(result)
 }
 
-fn count_towel_combos(
-    pattern: &str,
-    towels_by_ending: &HashMap<u8, Vec<&str>>,
-    prev: &[usize],
-) -> usize {
-    let last = pattern.as_bytes()[pattern.len() - 1];
-
-    towels_by_ending
-        .get(&last)
-        .into_iter()
-        .flatten()
-        .filter(|towel| pattern.ends_with(**towel))
-        .map(|towel| prev[pattern.len() - towel.len()])
-        .sum()
-}
-
 fn count_all_towel_combos(
     pattern: &str,
     towels_by_ending: &HashMap<u8, Vec<&str>>,
 ) -> usize {
-    let mut prev = Vec::with_capacity(pattern.len());
-    prev.push(1);
-
-    (1..=pattern.len())
-        .map(|e| &pattern[..e])
-        .for_each(|sub_pattern| {
-            prev.push(count_towel_combos(sub_pattern, towels_by_ending, &prev));
-        });
-
-    *prev.last().unwrap()
+    // keyed by the length of the prefix of `pattern` still to be covered
+    let mut memo = Memo::new();
+
+    memo.solve(
+        pattern.len(),
+        |&e| {
+            let prefix = &pattern[..e];
+
+            prefix
+                .as_bytes()
+                .last()
+                .and_then(|last| towels_by_ending.get(last))
+                .into_iter()
+                .flatten()
+                .filter(|towel| prefix.ends_with(**towel))
+                .map(|towel| e - towel.len())
+                .collect_vec()
+        },
+        |&e, answers| match e {
+            0 => 1,
+            _ => answers.iter().sum(),
+        },
+    )
 }
 
 pub fn part_two(input: &str) -> Option<usize> {
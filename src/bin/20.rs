@@ -1,6 +1,4 @@
-use std::collections::VecDeque;
-
-use advent_of_code::Grid;
+use advent_of_code::{search, Grid};
 use itertools::Itertools;
 use tinyvec::TinyVec;
 
@@ -31,16 +29,15 @@ fn fill_nominal_distances(grid: &mut Grid<CheatMapEntry>) {
         .find_map(|(i, e)| matches!(e, E::Path(0)).then_some(i))
         .expect("🥶");
 
-    let mut queue = [(end, 0)].into_iter().collect::<VecDeque<_>>();
+    let distances = search::bfs_distances(end, |curr| {
+        grid.neighbors(*curr)
+            .filter(|(_, n)| !matches!(&grid.data[*n], E::Wall))
+            .map(|(_, n)| n)
+    });
 
-    while let Some((curr, dist)) = queue.pop_front() {
-        grid.data[curr] = E::Path(dist);
-        queue.extend(
-            grid.neighbors(curr)
-                .filter(|(_, n)| matches!(&grid.data[*n], E::Path(usize::MAX)))
-                .map(|(_, n)| (n, dist + 1)),
-        )
-    }
+    distances
+        .into_iter()
+        .for_each(|(i, dist)| grid.data[i] = E::Path(dist));
 }
 
 fn p1_limited(input: &str, minimum_savings: usize) -> usize {
@@ -106,6 +103,9 @@ pub fn part_two(input: &str) -> Option<usize> {
 
 #[cfg(test)]
 mod tests {
+    use advent_of_code::generate::generator_for;
+    use rand::{rngs::StdRng, SeedableRng};
+
     use super::*;
 
     #[test]
@@ -143,4 +143,15 @@ mod tests {
         );
         assert_eq!(result, 29);
     }
+
+    #[test]
+    fn test_generated() {
+        let mut rng = StdRng::seed_from_u64(20);
+        let input = generator_for(DAY).unwrap().generate(41, &mut rng);
+
+        // every short cheat is also a long one
+        let short = p1_limited(&input, 20);
+        assert!(short > 0);
+        assert!(p2_limited(&input, 20) >= short);
+    }
 }
@@ -21,7 +21,6 @@ advent_of_code::solution!(21);
 ///    | < | v | > |
 ///    +---+---+---+
 /// 7 is at index 0 and index 9 is not accessible
-
 const BUTTON_COUNT: usize = 15;
 
 static GRID: Lazy<Grid<Button>> = Lazy::new(|| {
@@ -245,9 +244,11 @@ pub fn part_one(input: &str) -> Option<usize> {
 }
 
 pub fn part_two(input: &str) -> Option<usize> {
+    let mut rng = advent_of_code::rng::rng();
+
     (0..1000)
         .map(|_| {
-            let seed = rand::thread_rng().next_u64();
+            let seed = rng.next_u64();
             let all_paths = seeded_all_paths(seed);
 
             input
@@ -1,12 +1,10 @@
-use advent_of_code::ws;
+use advent_of_code::parse::{parse_all, unsigned_list};
 use itertools::Itertools;
-use nom::{character::complete::u64, multi::many1, IResult};
 
 advent_of_code::solution!(22);
 
 fn parse_input(input: &str) -> Vec<u64> {
-    let result: IResult<_, _> = many1(ws(u64))(input);
-    result.expect("🥊").1
+    parse_all(unsigned_list(), input).expect("🥊")
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
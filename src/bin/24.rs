@@ -1,10 +1,8 @@
-use core::panic;
-use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 
-use advent_of_code::ws;
+use advent_of_code::{graph::Graph, ws};
 use itertools::Itertools;
 use nom::{
-    branch::alt,
     bytes::complete::tag,
     character::complete::{alphanumeric1, char, u8},
     combinator::map,
@@ -81,54 +79,44 @@ fn parse_input(input: &str) -> (Vec<(&'_ str, u8)>, Vec<C<&'_ str>>) {
 
 pub fn part_one(input: &str) -> Option<u64> {
     let (nodes, edges) = parse_input(input);
-    let mut z_nodes = BTreeMap::new();
 
-    let name_map = nodes
-        .iter()
-        .map(|(name, _)| name)
-        .chain(edges.iter().flat_map(|c| c.nodes()))
-        .collect::<HashSet<_>>()
+    // wires point to the wires computed from them
+    let mut graph = Graph::new();
+    nodes.iter().for_each(|(name, _)| {
+        graph.add_node(name);
+    });
+
+    let edges = edges
         .into_iter()
-        .enumerate()
-        .map(|(i, name)| (*name, i))
-        .inspect(|(name, i)| {
-            if name.starts_with('z') {
-                z_nodes.insert(*name, *i);
-            }
+        .map(|C { l, r, o, g }| {
+            let (l, out) = graph.add_edge(l, o);
+            let (r, _) = graph.add_edge(r, o);
+            C { l, r, o: out, g }
         })
-        .collect::<HashMap<_, _>>();
+        .collect_vec();
 
-    let mut states = vec![None; name_map.len()];
+    let mut states = vec![None; graph.len()];
 
     nodes
         .into_iter()
-        .for_each(|(n, v)| states[*name_map.get(n).unwrap()] = Some(v));
-
-    let edges = edges
-        .into_iter()
-        .map(|C { l, r, o, g }| C {
-            l: *name_map.get(l).unwrap(),
-            r: *name_map.get(r).unwrap(),
-            o: *name_map.get(o).unwrap(),
-            g,
-        })
-        .collect_vec();
+        .for_each(|(n, v)| states[graph.id(n).unwrap()] = Some(v));
 
-    let mut queue = edges.iter().copied().collect::<VecDeque<_>>();
+    let mut gates = vec![None; graph.len()];
+    edges.into_iter().for_each(|c| gates[c.o] = Some(c));
 
-    while let Some(C { l, r, o, g }) = queue.pop_front() {
-        match (states[l], states[r]) {
-            (Some(n1), Some(n2)) => states[o] = Some(g.eval(n1, n2)),
-            _ => queue.push_back(C { l, r, o, g }),
+    for n in graph.toposort().expect("🔁") {
+        if let Some(C { l, r, o, g }) = gates[n] {
+            states[o] = states[l].zip(states[r]).map(|(l, r)| g.eval(l, r));
         }
     }
 
-    let result = z_nodes
-        .into_values()
-        .filter_map(|i| states[i])
+    let result = (0..graph.len())
+        .filter(|n| graph.name(*n).starts_with('z'))
+        .sorted_by_key(|n| graph.name(*n))
+        .filter_map(|n| states[n])
         .map(|v| v as u64)
         .enumerate()
-        .map(|(i, curr)| (curr << i))
+        .map(|(i, curr)| curr << i)
         .sum();
 
     Some(result)
@@ -518,9 +506,8 @@ pub fn part_two(input: &str) -> Option<String> {
 
     let wrong_last_nodes = last_nodes
         .iter()
-        .enumerate()
-        .filter_map(|(i, n)| n.map(|v| (i, v)))
-        .filter_map(|(i, n)| {
+        .flatten()
+        .filter_map(|&n| {
             let edge = edges_by_output[n].unwrap();
             if edge.g != G::A {
                 has_needs
@@ -545,35 +532,6 @@ pub fn part_two(input: &str) -> Option<String> {
         .map(|i| names[i])
         .collect_vec());
 
-    let maybe_edges = wrong_output_gate
-        .iter()
-        .copied()
-        .chain(wrong_top_bottom.iter().copied())
-        .chain(wrong_carry_nodes.iter().copied())
-        .chain(wrong_inter_nodes.iter().copied())
-        .chain(wrong_inter_2_nodes.iter().copied())
-        .chain(wrong_last_nodes.iter().copied())
-        .flat_map(|n| {
-            edges_by_output[n]
-                .iter()
-                .copied()
-                .copied()
-                .chain(edges_by_inputs[n].iter().copied())
-        })
-        .collect::<HashSet<_>>();
-
-    let multiple_appearances = maybe_edges
-        .iter()
-        .flat_map(|e| e.nodes())
-        .map(|n| (n, ()))
-        .into_group_map()
-        .into_iter()
-        .map(|(n, v)| (n, v.len()))
-        .sorted_by(|l, r| r.1.cmp(&l.1))
-        //.take(10)
-        .collect_vec();
-
-    //dbg!(&multiple_appearances);
     let result = wrong_output_gate
         .into_iter()
         .chain(wrong_top_bottom)
@@ -595,7 +553,6 @@ pub fn part_two(input: &str) -> Option<String> {
 
     // dbg!(&carry_nodes);
     // dbg!(&inter_nodes);
-    //dbg!(maybe_edges);
 
     None
 }
@@ -620,6 +577,9 @@ pub fn part_two(input: &str) -> Option<String> {
 //
 #[cfg(test)]
 mod tests {
+    use advent_of_code::generate::Adder;
+    use rand::{rngs::StdRng, SeedableRng};
+
     use super::*;
 
     #[test]
@@ -635,4 +595,42 @@ mod tests {
             part_two(&advent_of_code::template::read_file("inputs", DAY));
         assert_eq!(result, None);
     }
+
+    #[test]
+    fn test_generated() {
+        let mut rng = StdRng::seed_from_u64(24);
+        let (input, swapped) = Adder.generate_planted(45, &mut rng);
+        assert_eq!(swapped.len(), 4);
+
+        // swapping the outputs back gives a working adder
+        let fixed = input
+            .lines()
+            .map(|line| {
+                let Some((gate, out)) = line.split_once(" -> ") else {
+                    return line.to_owned();
+                };
+                let out = swapped
+                    .iter()
+                    .find_map(|(l, r)| match out {
+                        o if o == l => Some(r),
+                        o if o == r => Some(l),
+                        _ => None,
+                    })
+                    .map_or(out, String::as_str);
+                format!("{gate} -> {out}")
+            })
+            .join("\n");
+
+        let number = |wire: char| {
+            parse_input(&input)
+                .0
+                .into_iter()
+                .filter(|(name, _)| name.starts_with(wire))
+                .map(|(name, v)| {
+                    u64::from(v) << name[1..].parse::<u64>().unwrap()
+                })
+                .sum::<u64>()
+        };
+        assert_eq!(part_one(&fixed), Some(number('x') + number('y')));
+    }
 }
@@ -1,6 +1,5 @@
 use advent_of_code::{Compass, Grid};
 use itertools::Itertools;
-use nom::{bytes::complete::tag, multi::separated_list1, sequence::terminated};
 
 advent_of_code::solution!(25);
 
@@ -41,9 +40,8 @@ impl V {
 pub fn part_one(input: &str) -> Option<usize> {
     let mut h = None;
 
-    let vs = input
-        .split("\n\n")
-        .map(Grid::parse_lines)
+    let vs = Grid::parse_many(input)
+        .into_iter()
         .inspect(|g| {
             if let Some(hs) = h {
                 assert_eq!(g.height, hs);
@@ -72,7 +70,7 @@ pub fn part_one(input: &str) -> Option<usize> {
 
     let result = ks
         .into_iter()
-        .cartesian_product(ls.into_iter())
+        .cartesian_product(ls)
         .filter(|(k, l)| {
             k.iter()
                 .copied()
@@ -84,7 +82,7 @@ pub fn part_one(input: &str) -> Option<usize> {
     Some(result)
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
+pub fn part_two(_input: &str) -> Option<u32> {
     None
 }
 
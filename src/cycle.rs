@@ -0,0 +1,240 @@
+//! Cycle detection for simulations that eventually repeat a state. The
+//! simulation is a `step` closure from one state to the next, and a found
+//! [`Cycle`] lets the state after any number of steps be computed without
+//! running all of them.
+
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    hash::Hash,
+};
+
+/// Where a sequence of states starts repeating. The states after `start`
+/// steps and after `start + period` steps are the first repeated pair.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cycle {
+    /// Steps before the first state that is part of the cycle
+    pub start: usize,
+    /// Length of the cycle
+    pub period: usize,
+}
+
+impl Cycle {
+    /// The fewest steps that reach the same state as `n` steps
+    pub fn reduce(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.period
+        }
+    }
+
+    /// The state after `n` steps from `start`, running at most
+    /// `start + period` of them
+    pub fn fast_forward<S, F>(&self, start: S, mut step: F, n: usize) -> S
+    where
+        F: FnMut(&S) -> S,
+    {
+        (0..self.reduce(n)).fold(start, |state, _| step(&state))
+    }
+}
+
+/// Floyd's tortoise and hare. Only two states are kept at a time, but the
+/// sequence is stepped about three times over.
+pub fn floyd<S, F>(start: S, mut step: F) -> Cycle
+where
+    S: PartialEq,
+    F: FnMut(&S) -> S,
+{
+    let mut tortoise = step(&start);
+    let mut hare = step(&tortoise);
+
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        let halfway = step(&hare);
+        hare = step(&halfway);
+    }
+
+    // the hare is now a multiple of the period ahead, so both meet again at
+    // the start of the cycle when the tortoise starts over
+    let mut tortoise = start;
+    let mut cycle_start = 0;
+
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        cycle_start += 1;
+    }
+
+    let mut hare = step(&tortoise);
+    let mut period = 1;
+
+    while tortoise != hare {
+        hare = step(&hare);
+        period += 1;
+    }
+
+    Cycle {
+        start: cycle_start,
+        period,
+    }
+}
+
+/// Brent's algorithm. Like [`floyd`] it keeps two states at a time, but it
+/// usually takes fewer steps.
+pub fn brent<S, F>(start: S, mut step: F) -> Cycle
+where
+    S: Clone + PartialEq,
+    F: FnMut(&S) -> S,
+{
+    let mut power = 1;
+    let mut period = 1;
+    let mut tortoise = start.clone();
+    let mut hare = step(&start);
+
+    while tortoise != hare {
+        if power == period {
+            tortoise = hare.clone();
+            power *= 2;
+            period = 0;
+        }
+
+        hare = step(&hare);
+        period += 1;
+    }
+
+    let mut tortoise = start.clone();
+    let mut hare = (0..period).fold(start, |state, _| step(&state));
+    let mut cycle_start = 0;
+
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        cycle_start += 1;
+    }
+
+    Cycle {
+        start: cycle_start,
+        period,
+    }
+}
+
+/// Find the cycle by remembering every state by `key`. Steps each state only
+/// once, and also returns the states before the repeat, so the state after
+/// `n` steps is `states[cycle.reduce(n)]`.
+pub fn find_cycle_by_key<S, K, F, G>(
+    start: S,
+    mut step: F,
+    mut key: G,
+) -> (Cycle, Vec<S>)
+where
+    K: Eq + Hash,
+    F: FnMut(&S) -> S,
+    G: FnMut(&S) -> K,
+{
+    let mut seen = HashMap::new();
+    let mut states = vec![];
+    let mut state = start;
+
+    loop {
+        match seen.entry(key(&state)) {
+            Entry::Occupied(entry) => {
+                let cycle = Cycle {
+                    start: *entry.get(),
+                    period: states.len() - entry.get(),
+                };
+                return (cycle, states);
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(states.len());
+            }
+        }
+
+        let next = step(&state);
+        states.push(state);
+        state = next;
+    }
+}
+
+/// [`find_cycle_by_key`] with the states themselves as keys
+pub fn find_cycle<S, F>(start: S, step: F) -> (Cycle, Vec<S>)
+where
+    S: Clone + Eq + Hash,
+    F: FnMut(&S) -> S,
+{
+    find_cycle_by_key(start, step, S::clone)
+}
+
+/// The state after `n` steps from `start`. Stops early if the states repeat
+/// before that and skips the remaining full cycles.
+pub fn nth_state<S, F>(start: S, mut step: F, n: usize) -> S
+where
+    S: Clone + Eq + Hash,
+    F: FnMut(&S) -> S,
+{
+    let mut seen = HashMap::new();
+    let mut states = vec![];
+    let mut state = start;
+
+    while states.len() < n {
+        if let Some(&cycle_start) = seen.get(&state) {
+            let cycle = Cycle {
+                start: cycle_start,
+                period: states.len() - cycle_start,
+            };
+            return states.swap_remove(cycle.reduce(n));
+        }
+
+        seen.insert(state.clone(), states.len());
+        let next = step(&state);
+        states.push(state);
+        state = next;
+    }
+
+    state
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cycle_finders_agree() {
+        // 0 -> 1 -> 2 -> 3 -> 4 -> 5 -> 3
+        let next = [1, 2, 3, 4, 5, 3];
+        let step = |s: &usize| next[*s];
+        let expected = Cycle {
+            start: 3,
+            period: 3,
+        };
+
+        assert_eq!(floyd(0, step), expected);
+        assert_eq!(brent(0, step), expected);
+
+        let (cycle, states) = find_cycle(0, step);
+        assert_eq!(cycle, expected);
+        assert_eq!(states, [0, 1, 2, 3, 4, 5]);
+
+        let step = |x: &u64| (x * x + 1) % 255;
+        let (cycle, _) = find_cycle(3, step);
+        assert_eq!(floyd(3, step), cycle);
+        assert_eq!(brent(3, step), cycle);
+
+        let (cycle, _) =
+            find_cycle_by_key((0, 'a'), |(s, c)| (next[*s], *c), |s| s.0);
+        assert_eq!(cycle, expected);
+    }
+
+    #[test]
+    fn fast_forward() {
+        let next = [1, 2, 3, 4, 5, 3];
+        let step = |s: &usize| next[*s];
+        let cycle = brent(0, step);
+
+        assert_eq!(cycle.reduce(2), 2);
+        assert_eq!(cycle.reduce(1_000_000_000), 4);
+        assert_eq!(cycle.fast_forward(0, step, 1_000_000_000), 4);
+        assert_eq!(nth_state(0, step, 1_000_000_000), 4);
+        assert_eq!(nth_state(0, step, 2), 2);
+        assert_eq!(nth_state(0, step, 0), 0);
+    }
+}
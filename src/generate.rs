@@ -0,0 +1,521 @@
+//! Synthetic puzzle inputs for stress testing solutions at sizes beyond the
+//! real input.
+
+use std::collections::HashSet;
+
+use itertools::Itertools;
+use rand::{seq::SliceRandom, Rng, RngCore};
+use strum::IntoEnumIterator;
+
+use crate::{math, Compass, Day, Grid};
+
+/// Produces puzzle inputs in the format of a single day. `size` is the
+/// day-specific scale knob (grid side length, robot count, bit width ...)
+pub trait InputGenerator {
+    /// Short description of what `size` controls for this generator
+    fn size_hint(&self) -> &'static str;
+
+    fn generate(&self, size: usize, rng: &mut dyn RngCore) -> String;
+}
+
+/// Get the generator for the given day if there is one
+pub fn generator_for(day: Day) -> Option<Box<dyn InputGenerator>> {
+    let result: Box<dyn InputGenerator> = match day.into_inner() {
+        14 => Box::new(Robots::default()),
+        16 => Box::new(Maze {
+            extra_openings: 0.05,
+        }),
+        18 => Box::new(FallingBytes::default()),
+        20 => Box::new(Racetrack),
+        23 => Box::new(LanGraph { clique_size: 13 }),
+        24 => Box::new(Adder),
+        _ => return None,
+    };
+
+    Some(result)
+}
+
+/// Robots for day 14 in the form `p=x,y v=dx,dy`. `size` is the number of
+/// robots besides the 302 that draw a framed Christmas tree at one point
+/// within the first `width * height` seconds.
+pub struct Robots {
+    pub width: i64,
+    pub height: i64,
+}
+
+impl Default for Robots {
+    fn default() -> Self {
+        Self {
+            width: 101,
+            height: 103,
+        }
+    }
+}
+
+impl Robots {
+    /// Like [`InputGenerator::generate`], also returning the second at which
+    /// the tree shows up
+    pub fn generate_planted(
+        &self,
+        size: usize,
+        rng: &mut dyn RngCore,
+    ) -> (String, usize) {
+        let (width, height) = (self.width, self.height);
+        let at = rng.gen_range(1..math::lcm(width, height));
+
+        // a tree with a trunk in a 31 by 33 frame, like in the puzzle
+        let frame = (0..31)
+            .cartesian_product(0..33)
+            .filter(|&(x, y)| x == 0 || x == 30 || y == 0 || y == 32);
+        let crown = (0..13)
+            .flat_map(|row| (15 - row..=15 + row).map(move |x| (x, row + 4)));
+        let trunk = (14..=16).cartesian_product(17..20);
+        let left = rng.gen_range(0..=width - 31);
+        let top = rng.gen_range(0..=height - 33);
+
+        let mut robots = frame
+            .chain(crown)
+            .chain(trunk)
+            .map(|(x, y)| (left + x, top + y))
+            .map(|(x, y)| {
+                let (dx, dy) = self.velocity(rng);
+                let px = (x - dx * at).rem_euclid(width);
+                let py = (y - dy * at).rem_euclid(height);
+                ((px, py), (dx, dy))
+            })
+            .collect_vec();
+
+        robots.extend((0..size).map(|_| {
+            let p = (rng.gen_range(0..width), rng.gen_range(0..height));
+            (p, self.velocity(rng))
+        }));
+        robots.shuffle(rng);
+
+        let input = robots
+            .into_iter()
+            .map(|((px, py), (dx, dy))| format!("p={px},{py} v={dx},{dy}"))
+            .join("\n");
+
+        (input, at as usize)
+    }
+
+    fn velocity(&self, rng: &mut dyn RngCore) -> (i64, i64) {
+        (
+            rng.gen_range(-self.width + 1..self.width),
+            rng.gen_range(-self.height + 1..self.height),
+        )
+    }
+}
+
+impl InputGenerator for Robots {
+    fn size_hint(&self) -> &'static str {
+        "robot count"
+    }
+
+    fn generate(&self, size: usize, rng: &mut dyn RngCore) -> String {
+        self.generate_planted(size, rng).0
+    }
+}
+
+/// Reindeer maze for day 16 with `S` in the bottom left and `E` in the top
+/// right. `size` is the side length of the square grid. A fraction of the
+/// interior walls are knocked out so there are multiple best paths.
+pub struct Maze {
+    pub extra_openings: f64,
+}
+
+impl InputGenerator for Maze {
+    fn size_hint(&self) -> &'static str {
+        "grid side length"
+    }
+
+    fn generate(&self, size: usize, rng: &mut dyn RngCore) -> String {
+        let mut grid = carve_maze(size, rng);
+        let size = grid.width;
+
+        for i in 0..grid.data.len() {
+            let (col, row) = grid.to_col_row(i);
+            let interior =
+                col > 0 && row > 0 && col < size - 1 && row < size - 1;
+
+            if interior
+                && grid.data[i] == '#'
+                && (col % 2 == 1 || row % 2 == 1)
+                && rng.gen_bool(self.extra_openings)
+            {
+                grid.data[i] = '.';
+            }
+        }
+
+        let start = grid.to_index(1, size - 2);
+        let end = grid.to_index(size - 2, 1);
+        grid.data[start] = 'S';
+        grid.data[end] = 'E';
+
+        render(&grid)
+    }
+}
+
+/// Falling byte coordinates for day 18 as `col,row` lines. `size` is the
+/// number of bytes. Like the puzzle input, the first `first_fall` bytes (and
+/// the one after) leave a path between the corners of the `side` by `side`
+/// memory space, and all of them cut it off.
+pub struct FallingBytes {
+    pub side: usize,
+    pub first_fall: usize,
+}
+
+impl Default for FallingBytes {
+    fn default() -> Self {
+        Self {
+            side: 71,
+            first_fall: 1024,
+        }
+    }
+}
+
+impl InputGenerator for FallingBytes {
+    fn size_hint(&self) -> &'static str {
+        "byte count"
+    }
+
+    fn generate(&self, size: usize, rng: &mut dyn RngCore) -> String {
+        let side = self.side;
+        let last = side * side - 1;
+
+        // keep a random staircase path and both exits of the start corner
+        // free until the first bytes have fallen
+        let mut steps = [vec![1; side - 1], vec![side; side - 1]].concat();
+        steps.shuffle(rng);
+        let mut kept = steps
+            .into_iter()
+            .scan(0, |curr, step| {
+                *curr += step;
+                Some(*curr)
+            })
+            .collect::<HashSet<_>>();
+        kept.extend([0, 1, side]);
+
+        let (mut first, mut rest): (Vec<_>, Vec<_>) =
+            (1..last).partition(|i| !kept.contains(i));
+        first.shuffle(rng);
+        rest.extend(first.drain((self.first_fall + 1).min(first.len())..));
+
+        // both exits of the start corner are among the bytes, so all of them
+        // together block the path
+        rest.retain(|i| *i != 1 && *i != side);
+        rest.shuffle(rng);
+        rest.truncate(size.saturating_sub(first.len() + 2));
+        rest.extend([1, side]);
+        rest.shuffle(rng);
+
+        first
+            .into_iter()
+            .chain(rest)
+            .map(|i| format!("{},{}", i % side, i / side))
+            .join("\n")
+    }
+}
+
+/// Single-lane racetrack for day 20. `size` is the side length of the grid.
+/// The track is the unique path between two cells of a random maze, so
+/// every track cell has at most two track neighbors.
+pub struct Racetrack;
+
+impl InputGenerator for Racetrack {
+    fn size_hint(&self) -> &'static str {
+        "grid side length"
+    }
+
+    fn generate(&self, size: usize, rng: &mut dyn RngCore) -> String {
+        let maze = carve_maze(size, rng);
+        let start = maze.to_index(1, 1);
+        let path = farthest_path(&maze, start);
+
+        let mut grid = Grid::new(vec!['#'; maze.data.len()], maze.width);
+        path.iter().for_each(|i| grid.data[*i] = '.');
+        grid.data[start] = 'S';
+        grid.data[*path.last().unwrap()] = 'E';
+
+        render(&grid)
+    }
+}
+
+/// LAN party connections for day 23 as `ab-cd` lines. `size` is the number of
+/// computers (at most 676). Every computer gets a few random links and one
+/// clique of `clique_size` computers is planted for part two.
+pub struct LanGraph {
+    pub clique_size: usize,
+}
+
+impl InputGenerator for LanGraph {
+    fn size_hint(&self) -> &'static str {
+        "computer count"
+    }
+
+    fn generate(&self, size: usize, rng: &mut dyn RngCore) -> String {
+        let mut names = (b'a'..=b'z')
+            .cartesian_product(b'a'..=b'z')
+            .map(|(a, b)| format!("{}{}", char::from(a), char::from(b)))
+            .collect_vec();
+        names.shuffle(rng);
+        names.truncate(size.clamp(2, names.len()));
+
+        let mut edges = HashSet::new();
+        let mut add_edge = |l: usize, r: usize| {
+            if l != r {
+                edges.insert((l.min(r), l.max(r)));
+            }
+        };
+
+        let clique_size = self.clique_size.min(names.len());
+        (0..clique_size)
+            .tuple_combinations()
+            .for_each(|(l, r)| add_edge(l, r));
+
+        (0..names.len()).for_each(|l| {
+            (0..3).for_each(|_| add_edge(l, rng.gen_range(0..names.len())))
+        });
+
+        let mut edges = edges.into_iter().collect_vec();
+        edges.sort_unstable();
+        edges.shuffle(rng);
+
+        edges
+            .into_iter()
+            .map(|(l, r)| {
+                if rng.gen_bool(0.5) {
+                    format!("{}-{}", names[l], names[r])
+                } else {
+                    format!("{}-{}", names[r], names[l])
+                }
+            })
+            .join("\n")
+    }
+}
+
+/// Gate circuit for day 24: a ripple-carry adder with random input values
+/// and the outputs of four gate pairs swapped, each pair within the gates of
+/// one bit. `size` is the bit width of the `x` and `y` inputs, at least 6.
+pub struct Adder;
+
+impl Adder {
+    /// Like [`InputGenerator::generate`], also returning the pairs of wires
+    /// whose gates were swapped
+    pub fn generate_planted(
+        &self,
+        size: usize,
+        rng: &mut dyn RngCore,
+    ) -> (String, Vec<(String, String)>) {
+        let bits = size.clamp(6, 99);
+
+        let mut used = HashSet::new();
+        let mut fresh = |rng: &mut dyn RngCore| loop {
+            let name = (0..3)
+                .map(|_| char::from(rng.gen_range(b'a'..=b'w')))
+                .collect::<String>();
+            if used.insert(name.clone()) {
+                break name;
+            }
+        };
+
+        let swapped_bits = (1..bits - 1).collect_vec();
+        let swapped_bits = swapped_bits
+            .choose_multiple(rng, 4)
+            .copied()
+            .collect::<HashSet<_>>();
+
+        let mut gates = vec![];
+        let mut swapped = vec![];
+        let mut carry = String::new();
+
+        for bit in 0..bits {
+            let (x, y, z) = (
+                format!("x{bit:02}"),
+                format!("y{bit:02}"),
+                format!("z{bit:02}"),
+            );
+
+            if bit == 0 {
+                gates.push(format!("{x} XOR {y} -> {z}"));
+                carry = fresh(rng);
+                gates.push(format!("{x} AND {y} -> {carry}"));
+                continue;
+            }
+
+            let (sum, direct, indirect) = (fresh(rng), fresh(rng), fresh(rng));
+            let next_carry = if bit == bits - 1 {
+                format!("z{bits:02}")
+            } else {
+                fresh(rng)
+            };
+
+            // sum, direct, z, indirect and next carry, in the gate order below
+            let mut outputs = [&sum, &direct, &z, &indirect, &next_carry];
+            if swapped_bits.contains(&bit) {
+                let (a, b) =
+                    *[(0, 1), (2, 1), (2, 3), (2, 4)].choose(rng).unwrap();
+                outputs.swap(a, b);
+                swapped.push((outputs[a].clone(), outputs[b].clone()));
+            }
+
+            let [o_sum, o_direct, o_z, o_indirect, o_carry] = outputs;
+            gates.push(format!("{x} XOR {y} -> {o_sum}"));
+            gates.push(format!("{x} AND {y} -> {o_direct}"));
+            gates.push(format!("{sum} XOR {carry} -> {o_z}"));
+            gates.push(format!("{sum} AND {carry} -> {o_indirect}"));
+            gates.push(format!("{direct} OR {indirect} -> {o_carry}"));
+
+            carry = next_carry;
+        }
+
+        gates.shuffle(rng);
+
+        let wires = ['x', 'y']
+            .into_iter()
+            .flat_map(|w| (0..bits).map(move |bit| (w, bit)))
+            .map(|(w, bit)| format!("{w}{bit:02}: {}", rng.gen_range(0..=1)))
+            .collect_vec();
+
+        (
+            format!("{}\n\n{}", wires.join("\n"), gates.join("\n")),
+            swapped,
+        )
+    }
+}
+
+impl InputGenerator for Adder {
+    fn size_hint(&self) -> &'static str {
+        "input bit width"
+    }
+
+    fn generate(&self, size: usize, rng: &mut dyn RngCore) -> String {
+        self.generate_planted(size, rng).0
+    }
+}
+
+/// Carve a perfect maze with a randomized depth-first search. The result is
+/// square with an odd side length of at least 5 and a solid border. Open cells
+/// are at odd columns and rows.
+fn carve_maze(size: usize, rng: &mut dyn RngCore) -> Grid<char> {
+    let size = size.max(5) | 1;
+    let mut grid = Grid::new(vec!['#'; size * size], size);
+
+    let start = grid.to_index(1, 1);
+    grid.data[start] = '.';
+    let mut stack = vec![start];
+
+    while let Some(&curr) = stack.last() {
+        let options = Compass::iter()
+            .filter_map(|dir| {
+                let wall = grid.step_from_index(curr, dir)?;
+                let next = grid.step_from_index(wall, dir)?;
+                (!grid.is_border(next) && grid.data[next] == '#')
+                    .then_some((wall, next))
+            })
+            .collect_vec();
+
+        match options.choose(rng) {
+            Some(&(wall, next)) => {
+                grid.data[wall] = '.';
+                grid.data[next] = '.';
+                stack.push(next);
+            }
+            None => {
+                stack.pop();
+            }
+        }
+    }
+
+    grid
+}
+
+/// Get the cells on the path from `start` to the open cell farthest from it
+fn farthest_path(grid: &Grid<char>, start: usize) -> Vec<usize> {
+    let mut parents = vec![usize::MAX; grid.data.len()];
+    let mut stack = vec![(start, 0)];
+    let (mut far, mut far_dist) = (start, 0);
+    parents[start] = start;
+
+    while let Some((curr, dist)) = stack.pop() {
+        if dist > far_dist {
+            (far, far_dist) = (curr, dist);
+        }
+
+        grid.neighbors(curr)
+            .filter(|(_, n)| grid.data[*n] != '#' && parents[*n] == usize::MAX)
+            .collect_vec()
+            .into_iter()
+            .for_each(|(_, n)| {
+                parents[n] = curr;
+                stack.push((n, dist + 1));
+            });
+    }
+
+    let mut path = vec![far];
+    while *path.last().unwrap() != start {
+        path.push(parents[*path.last().unwrap()]);
+    }
+    path.reverse();
+
+    path
+}
+
+fn render(grid: &Grid<char>) -> String {
+    grid.rows()
+        .map(|row| row.iter().collect::<String>())
+        .join("\n")
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::day;
+
+    #[test]
+    fn racetrack_is_single_lane() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let input = Racetrack.generate(21, &mut rng);
+        let grid: Grid<char> = Grid::parse_lines(&input);
+
+        assert_eq!(grid.width, 21);
+        assert_eq!(grid.data.iter().filter(|c| **c == 'S').count(), 1);
+        assert_eq!(grid.data.iter().filter(|c| **c == 'E').count(), 1);
+
+        let branches = (0..grid.data.len())
+            .filter(|i| grid.data[*i] != '#')
+            .filter(|i| {
+                grid.neighbors(*i)
+                    .filter(|(_, n)| grid.data[*n] != '#')
+                    .count()
+                    > 2
+            })
+            .count();
+        assert_eq!(branches, 0);
+    }
+
+    #[test]
+    fn adder_has_all_gates() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let input = Adder.generate(8, &mut rng);
+        let (wires, gates) = input.split_once("\n\n").unwrap();
+
+        assert_eq!(wires.lines().count(), 16);
+        assert_eq!(gates.lines().count(), 2 + 7 * 5);
+        assert!(gates.contains("-> z08"));
+    }
+
+    #[test]
+    fn generators_are_deterministic() {
+        [14, 16, 18, 20, 23, 24].into_iter().for_each(|d| {
+            let generator = generator_for(Day::new(d).unwrap()).unwrap();
+            let first = generator.generate(15, &mut StdRng::seed_from_u64(3));
+            let second = generator.generate(15, &mut StdRng::seed_from_u64(3));
+            assert_eq!(first, second);
+        });
+
+        assert!(generator_for(day!(1)).is_none());
+    }
+}
@@ -0,0 +1,500 @@
+//! Graphs over named nodes. Names are interned to dense ids on insertion, so
+//! the algorithms work on `usize` ids and plain vectors, and [`Graph::name`]
+//! maps the results back.
+
+use std::{collections::HashMap, fmt};
+
+/// Disjoint sets of `0..n` with path compression and union by size
+#[derive(Debug, Clone)]
+pub struct UnionFind {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+    sets: usize,
+}
+
+impl UnionFind {
+    pub fn new(n: usize) -> Self {
+        Self {
+            parent: (0..n).collect(),
+            size: vec![1; n],
+            sets: n,
+        }
+    }
+
+    /// The representative of the set containing `x`
+    pub fn find(&mut self, x: usize) -> usize {
+        let mut root = x;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+
+        let mut curr = x;
+        while self.parent[curr] != root {
+            curr = std::mem::replace(&mut self.parent[curr], root);
+        }
+
+        root
+    }
+
+    /// Merge the sets of `a` and `b`. Returns false if they already were one.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+
+        let (big, small) = if self.size[a] < self.size[b] {
+            (b, a)
+        } else {
+            (a, b)
+        };
+        self.parent[small] = big;
+        self.size[big] += self.size[small];
+        self.sets -= 1;
+
+        true
+    }
+
+    pub fn same(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// Size of the set containing `x`
+    pub fn size_of(&mut self, x: usize) -> usize {
+        let root = self.find(x);
+        self.size[root]
+    }
+
+    /// Number of disjoint sets
+    pub fn count(&self) -> usize {
+        self.sets
+    }
+
+    /// The members of every set, in ascending order
+    pub fn groups(&mut self) -> Vec<Vec<usize>> {
+        let mut groups = HashMap::<usize, Vec<usize>>::new();
+        for x in 0..self.parent.len() {
+            groups.entry(self.find(x)).or_default().push(x);
+        }
+
+        let mut groups = groups.into_values().collect::<Vec<_>>();
+        groups.sort_unstable();
+        groups
+    }
+}
+
+/// Returned by [`Graph::toposort`] when the graph isn't acyclic
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CycleError {
+    /// Nodes of one cycle, each having an edge to the next and the last one
+    /// to the first
+    pub cycle: Vec<usize>,
+}
+
+impl fmt::Display for CycleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "graph has a cycle of {} nodes", self.cycle.len())
+    }
+}
+
+impl std::error::Error for CycleError {}
+
+/// A directed graph with interned node names. Undirected graphs add every
+/// edge both ways with [`Graph::add_undirected_edge`].
+#[derive(Debug, Clone, Default)]
+pub struct Graph<'a> {
+    ids: HashMap<&'a str, usize>,
+    names: Vec<&'a str>,
+    edges: Vec<Vec<usize>>,
+}
+
+impl<'a> Graph<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The id of `name`, adding it as a node if it's new
+    pub fn add_node(&mut self, name: &'a str) -> usize {
+        *self.ids.entry(name).or_insert_with(|| {
+            self.names.push(name);
+            self.edges.push(vec![]);
+            self.names.len() - 1
+        })
+    }
+
+    /// Add an edge, and the nodes if they are new. Returns their ids.
+    pub fn add_edge(&mut self, from: &'a str, to: &'a str) -> (usize, usize) {
+        let (from, to) = (self.add_node(from), self.add_node(to));
+        self.edges[from].push(to);
+        (from, to)
+    }
+
+    pub fn add_undirected_edge(
+        &mut self,
+        a: &'a str,
+        b: &'a str,
+    ) -> (usize, usize) {
+        let (a, b) = self.add_edge(a, b);
+        self.edges[b].push(a);
+        (a, b)
+    }
+
+    pub fn id(&self, name: &str) -> Option<usize> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn name(&self, id: usize) -> &'a str {
+        self.names[id]
+    }
+
+    /// All names, indexed by id
+    pub fn names(&self) -> &[&'a str] {
+        &self.names
+    }
+
+    /// Number of nodes
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    pub fn neighbors(&self, id: usize) -> &[usize] {
+        &self.edges[id]
+    }
+
+    pub fn has_edge(&self, from: usize, to: usize) -> bool {
+        self.edges[from].contains(&to)
+    }
+
+    /// The nodes ordered so every edge points forward, or a cycle that makes
+    /// that impossible
+    pub fn toposort(&self) -> Result<Vec<usize>, CycleError> {
+        let mut in_degree = vec![0; self.len()];
+        self.edges
+            .iter()
+            .flatten()
+            .for_each(|&to| in_degree[to] += 1);
+
+        let mut order = (0..self.len())
+            .filter(|&n| in_degree[n] == 0)
+            .collect::<Vec<_>>();
+        let mut next = 0;
+
+        while let Some(&curr) = order.get(next) {
+            next += 1;
+
+            for &to in &self.edges[curr] {
+                in_degree[to] -= 1;
+                if in_degree[to] == 0 {
+                    order.push(to);
+                }
+            }
+        }
+
+        if order.len() == self.len() {
+            return Ok(order);
+        }
+
+        // every node left has an edge from another node left, so walking
+        // those edges backwards has to run into a cycle
+        let mut from = vec![None; self.len()];
+        for (n, edges) in self.edges.iter().enumerate() {
+            for &to in edges {
+                if in_degree[n] > 0 && in_degree[to] > 0 {
+                    from[to] = Some(n);
+                }
+            }
+        }
+
+        let start = (0..self.len()).find(|&n| in_degree[n] > 0).unwrap();
+        let mut seen = vec![false; self.len()];
+        let mut curr = start;
+        while !seen[curr] {
+            seen[curr] = true;
+            curr = from[curr].expect("left nodes have a predecessor left");
+        }
+
+        let mut cycle = vec![curr];
+        let mut prev = from[curr].unwrap();
+        while prev != curr {
+            cycle.push(prev);
+            prev = from[prev].unwrap();
+        }
+        cycle.reverse();
+
+        Err(CycleError { cycle })
+    }
+
+    /// Tarjan's strongly connected components, without recursion. Components
+    /// come in reverse topological order: no edge leads to a later one.
+    pub fn strongly_connected_components(&self) -> Vec<Vec<usize>> {
+        let n = self.len();
+        let mut index = vec![usize::MAX; n];
+        let mut low_link = vec![0; n];
+        let mut on_stack = vec![false; n];
+        let mut stack = vec![];
+        let mut components = vec![];
+        let mut next_index = 0;
+
+        for root in 0..n {
+            if index[root] != usize::MAX {
+                continue;
+            }
+
+            // (node, how many of its edges were followed)
+            let mut work = vec![(root, 0)];
+
+            while let Some((curr, edge)) = work.pop() {
+                if edge == 0 {
+                    index[curr] = next_index;
+                    low_link[curr] = next_index;
+                    next_index += 1;
+                    stack.push(curr);
+                    on_stack[curr] = true;
+                } else {
+                    let child = self.edges[curr][edge - 1];
+                    low_link[curr] = low_link[curr].min(low_link[child]);
+                }
+
+                let unvisited = self.edges[curr][edge..]
+                    .iter()
+                    .enumerate()
+                    .find_map(|(i, &to)| {
+                        if index[to] == usize::MAX {
+                            return Some((edge + i, to));
+                        }
+                        if on_stack[to] {
+                            low_link[curr] = low_link[curr].min(index[to]);
+                        }
+                        None
+                    });
+
+                if let Some((i, to)) = unvisited {
+                    work.push((curr, i + 1));
+                    work.push((to, 0));
+                } else if low_link[curr] == index[curr] {
+                    let mut component = vec![];
+                    while let Some(member) = stack.pop() {
+                        on_stack[member] = false;
+                        component.push(member);
+                        if member == curr {
+                            break;
+                        }
+                    }
+                    components.push(component);
+                }
+            }
+        }
+
+        components
+    }
+
+    /// Every triangle of an undirected graph as ascending ids
+    pub fn triangles(&self) -> Vec<[usize; 3]> {
+        let neighbors = self.sorted_neighbors();
+
+        (0..self.len())
+            .flat_map(|a| {
+                let neighbors = &neighbors;
+                neighbors[a].iter().filter(move |&&b| b > a).flat_map(
+                    move |&b| {
+                        neighbors[b]
+                            .iter()
+                            .filter(move |&&c| {
+                                c > b && neighbors[a].binary_search(&c).is_ok()
+                            })
+                            .map(move |&c| [a, b, c])
+                    },
+                )
+            })
+            .collect()
+    }
+
+    /// Every maximal clique of an undirected graph as ascending ids, found
+    /// with Bron–Kerbosch with pivoting
+    pub fn maximal_cliques(&self) -> Vec<Vec<usize>> {
+        let neighbors = self.sorted_neighbors();
+        let mut cliques = vec![];
+
+        bron_kerbosch(
+            &neighbors,
+            &mut vec![],
+            (0..self.len()).collect(),
+            vec![],
+            &mut cliques,
+        );
+
+        cliques
+    }
+
+    /// The largest clique of an undirected graph, the first one by ids if
+    /// there are several
+    pub fn max_clique(&self) -> Vec<usize> {
+        self.maximal_cliques()
+            .into_iter()
+            .min_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)))
+            .unwrap_or_default()
+    }
+
+    fn sorted_neighbors(&self) -> Vec<Vec<usize>> {
+        self.edges
+            .iter()
+            .enumerate()
+            .map(|(n, edges)| {
+                let mut edges = edges.clone();
+                edges.sort_unstable();
+                edges.dedup();
+                edges.retain(|&m| m != n);
+                edges
+            })
+            .collect()
+    }
+}
+
+/// The values of sorted `a` that are also in sorted `b`
+fn sorted_intersection(a: &[usize], b: &[usize]) -> Vec<usize> {
+    a.iter()
+        .copied()
+        .filter(|x| b.binary_search(x).is_ok())
+        .collect()
+}
+
+/// Extend `clique` by the `candidates`, none of which may be in `excluded`
+/// for the clique to be maximal. All sets are sorted ids.
+fn bron_kerbosch(
+    neighbors: &[Vec<usize>],
+    clique: &mut Vec<usize>,
+    mut candidates: Vec<usize>,
+    mut excluded: Vec<usize>,
+    cliques: &mut Vec<Vec<usize>>,
+) {
+    if candidates.is_empty() {
+        if excluded.is_empty() {
+            let mut found = clique.clone();
+            found.sort_unstable();
+            cliques.push(found);
+        }
+        return;
+    }
+
+    // any maximal clique contains the pivot or one of its non-neighbors
+    let pivot = candidates
+        .iter()
+        .chain(&excluded)
+        .copied()
+        .max_by_key(|&p| sorted_intersection(&candidates, &neighbors[p]).len())
+        .unwrap();
+    let branches = candidates
+        .iter()
+        .copied()
+        .filter(|v| neighbors[pivot].binary_search(v).is_err())
+        .collect::<Vec<_>>();
+
+    for v in branches {
+        clique.push(v);
+        bron_kerbosch(
+            neighbors,
+            clique,
+            sorted_intersection(&candidates, &neighbors[v]),
+            sorted_intersection(&excluded, &neighbors[v]),
+            cliques,
+        );
+        clique.pop();
+
+        candidates.retain(|&c| c != v);
+        let i = excluded.partition_point(|&x| x < v);
+        excluded.insert(i, v);
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use itertools::Itertools;
+
+    use super::*;
+
+    #[test]
+    fn union_find() {
+        let mut sets = UnionFind::new(6);
+        assert!(sets.union(0, 1));
+        assert!(sets.union(4, 5));
+        assert!(sets.union(1, 5));
+        assert!(!sets.union(0, 4));
+
+        assert!(sets.same(0, 5) && !sets.same(0, 2));
+        assert_eq!(sets.size_of(4), 4);
+        assert_eq!(sets.count(), 3);
+        assert_eq!(sets.groups(), [vec![0, 1, 4, 5], vec![2], vec![3]]);
+    }
+
+    #[test]
+    fn directed_orders() {
+        let mut graph = Graph::new();
+        for (from, to) in [("a", "b"), ("b", "c"), ("a", "c"), ("c", "d")] {
+            graph.add_edge(from, to);
+        }
+
+        let order = graph.toposort().unwrap();
+        let names = order.iter().map(|&n| graph.name(n)).collect::<String>();
+        assert_eq!(names, "abcd");
+
+        graph.add_edge("d", "b");
+        let err = graph.toposort().unwrap_err();
+        let mut cycle =
+            err.cycle.iter().map(|&n| graph.name(n)).collect::<Vec<_>>();
+        cycle.sort();
+        assert_eq!(cycle, ["b", "c", "d"]);
+        for (i, &n) in err.cycle.iter().enumerate() {
+            assert!(graph.has_edge(n, err.cycle[(i + 1) % err.cycle.len()]));
+        }
+
+        graph.add_edge("e", "a");
+        let components = graph.strongly_connected_components();
+        let names = components
+            .iter()
+            .map(|c| {
+                let mut names = c.iter().map(|&n| graph.name(n)).collect_vec();
+                names.sort();
+                names.concat()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["bcd", "a", "e"]);
+    }
+
+    #[test]
+    fn cliques() {
+        let mut graph = Graph::new();
+        for edge in ["a-b", "a-c", "b-c", "b-d", "c-d", "a-d", "d-e", "e-f"] {
+            let (a, b) = edge.split_once('-').unwrap();
+            graph.add_undirected_edge(a, b);
+        }
+
+        let ids = |names: &str| {
+            names
+                .chars()
+                .map(|c| graph.id(&c.to_string()).unwrap())
+                .collect_vec()
+        };
+
+        assert_eq!(graph.triangles().len(), 4);
+        assert_eq!(graph.max_clique(), ids("abcd"));
+
+        let mut cliques = graph.maximal_cliques();
+        cliques.sort();
+        assert_eq!(cliques, [ids("abcd"), ids("de"), ids("ef")]);
+
+        // bcd is found first, but aef comes first by ids
+        let mut graph = Graph::new();
+        for name in ["a", "b", "c", "d", "e", "f"] {
+            graph.add_node(name);
+        }
+        for edge in ["a-e", "a-f", "e-f", "b-c", "b-d", "c-d"] {
+            let (a, b) = edge.split_once('-').unwrap();
+            graph.add_undirected_edge(a, b);
+        }
+        assert_eq!(graph.max_clique(), [0, 4, 5]);
+    }
+}
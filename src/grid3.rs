@@ -0,0 +1,253 @@
+//! Three dimensional grids of voxels. Cells are stored layer by layer, so the
+//! index of `(x, y, z)` is `x + y * width + z * width * height`.
+
+use std::ops::{Add, AddAssign, Mul, Neg, Sub};
+
+use crate::{lattice::Lattice, Grid};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Vec3 {
+    pub x: i64,
+    pub y: i64,
+    pub z: i64,
+}
+
+impl Vec3 {
+    pub const ZERO: Self = Self::new(0, 0, 0);
+
+    /// Unit vectors towards the 6 cells sharing a face
+    pub const FACES: [Self; 6] = [
+        Self::new(1, 0, 0),
+        Self::new(-1, 0, 0),
+        Self::new(0, 1, 0),
+        Self::new(0, -1, 0),
+        Self::new(0, 0, 1),
+        Self::new(0, 0, -1),
+    ];
+
+    pub const fn new(x: i64, y: i64, z: i64) -> Self {
+        Self { x, y, z }
+    }
+
+    pub fn manhattan(self) -> u64 {
+        self.x.unsigned_abs() + self.y.unsigned_abs() + self.z.unsigned_abs()
+    }
+
+    /// Offsets to the 26 cells sharing a face, edge or corner
+    pub fn around() -> impl Iterator<Item = Self> {
+        (-1..=1)
+            .flat_map(|z| {
+                (-1..=1).flat_map(move |y| (-1..=1).map(move |x| (x, y, z)))
+            })
+            .map(|(x, y, z)| Self::new(x, y, z))
+            .filter(|v| *v != Self::ZERO)
+    }
+}
+
+impl From<(i64, i64, i64)> for Vec3 {
+    fn from((x, y, z): (i64, i64, i64)) -> Self {
+        Self::new(x, y, z)
+    }
+}
+
+impl Add for Vec3 {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        Self::new(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
+    }
+}
+
+impl Sub for Vec3 {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        Self::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+    }
+}
+
+impl Neg for Vec3 {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self::new(-self.x, -self.y, -self.z)
+    }
+}
+
+impl Mul<i64> for Vec3 {
+    type Output = Self;
+    fn mul(self, rhs: i64) -> Self {
+        Self::new(self.x * rhs, self.y * rhs, self.z * rhs)
+    }
+}
+
+impl AddAssign for Vec3 {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Grid3<T> {
+    pub width: usize,
+    pub height: usize,
+    pub depth: usize,
+    pub data: Vec<T>,
+}
+
+impl<T> Grid3<T> {
+    pub fn new(data: Vec<T>, width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            // an empty layer holds no cells, so it has no depth either
+            depth: data.len().checked_div(width * height).unwrap_or(0),
+            data,
+        }
+    }
+
+    /// Stack equally sized grids on top of each other, the first being `z = 0`
+    pub fn from_layers(layers: impl IntoIterator<Item = Grid<T>>) -> Self {
+        let mut layers = layers.into_iter().peekable();
+        let (width, height) =
+            layers.peek().map_or((0, 0), |l| (l.width, l.height));
+
+        let data = layers
+            .flat_map(|layer| {
+                assert_eq!(
+                    (layer.width, layer.height),
+                    (width, height),
+                    "layers must have the same size"
+                );
+                layer.data
+            })
+            .collect();
+
+        Self::new(data, width, height)
+    }
+
+    pub fn size(&self) -> Vec3 {
+        Vec3::new(self.width as i64, self.height as i64, self.depth as i64)
+    }
+
+    pub fn to_pos(&self, i: usize) -> Vec3 {
+        let layer = self.width * self.height;
+        let (z, rest) = (i / layer, i % layer);
+
+        Vec3::new(
+            (rest % self.width) as i64,
+            (rest / self.width) as i64,
+            z as i64,
+        )
+    }
+
+    pub fn contains(&self, pos: Vec3) -> bool {
+        let size = self.size();
+
+        (0..size.x).contains(&pos.x)
+            && (0..size.y).contains(&pos.y)
+            && (0..size.z).contains(&pos.z)
+    }
+
+    pub fn pos_to_index(&self, pos: Vec3) -> Option<usize> {
+        self.contains(pos).then(|| {
+            let (x, y, z) = (pos.x as usize, pos.y as usize, pos.z as usize);
+            x + (y + z * self.height) * self.width
+        })
+    }
+
+    pub fn get(&self, pos: Vec3) -> Option<&'_ T> {
+        self.pos_to_index(pos).map(|i| &self.data[i])
+    }
+
+    pub fn get_mut(&mut self, pos: Vec3) -> Option<&'_ mut T> {
+        self.pos_to_index(pos).map(|i| &mut self.data[i])
+    }
+
+    /// Step from `i` by the offset `dir`, which may be longer than one cell
+    pub fn step_from_index(&self, i: usize, dir: Vec3) -> Option<usize> {
+        self.pos_to_index(self.to_pos(i) + dir)
+    }
+
+    /// The up to 26 cells sharing a face, edge or corner with `i`
+    pub fn all_neighbors(
+        &self,
+        i: usize,
+    ) -> impl Iterator<Item = (Vec3, usize)> + '_ {
+        Vec3::around().filter_map(move |dir| {
+            self.step_from_index(i, dir).map(|j| (dir, j))
+        })
+    }
+}
+
+impl<T> Lattice for Grid3<T> {
+    type Cell = T;
+    type Dir = Vec3;
+
+    const DIRECTIONS: &'static [Vec3] = &Vec3::FACES;
+
+    fn cells(&self) -> &[T] {
+        &self.data
+    }
+
+    fn step(&self, i: usize, dir: Vec3) -> Option<usize> {
+        self.step_from_index(i, dir)
+    }
+
+    fn distance(&self, from: usize, to: usize) -> usize {
+        (self.to_pos(to) - self.to_pos(from)).manhattan() as usize
+    }
+
+    /// Layers of rows separated by blank lines, the first being `z = 0`
+    fn parse(input: &str) -> Self
+    where
+        T: From<char>,
+    {
+        if input.trim().is_empty() {
+            return Self::from_layers([]);
+        }
+        Self::from_layers(Grid::parse_many(input))
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn voxel_neighborhoods() {
+        let grid: Grid3<char> =
+            Grid3::parse("abc\ndef\nghi\n\njkl\nmno\npqr\n\nstu\nvwx\nyz.");
+        assert_eq!((grid.width, grid.height, grid.depth), (3, 3, 3));
+
+        let center = grid.pos_to_index(Vec3::new(1, 1, 1)).unwrap();
+        assert_eq!(grid.data[center], 'n');
+        assert_eq!(grid.to_pos(center), Vec3::new(1, 1, 1));
+        assert_eq!(grid.neighbors(center).count(), 6);
+        assert_eq!(grid.all_neighbors(center).count(), 26);
+        assert_eq!(grid.all_neighbors(0).count(), 7);
+
+        let column = grid
+            .ray(2, Vec3::new(0, 0, 1))
+            .map(|(_, c)| *c)
+            .collect::<String>();
+        assert_eq!(column, "clu");
+        assert_eq!(grid.distance(0, grid.data.len() - 1), 6);
+    }
+
+    #[test]
+    fn voxel_empty() {
+        let grid: Grid3<char> = Grid3::parse("");
+        assert_eq!((grid.width, grid.height, grid.depth), (0, 0, 0));
+        assert_eq!(Grid3::<char>::from_layers([]).depth, 0);
+    }
+
+    #[test]
+    fn voxel_flood_fill() {
+        let mut grid = Grid3::new(vec![false; 27], 3, 3);
+        for x in 0..3 {
+            *grid.get_mut(Vec3::new(x, 1, 1)).unwrap() = true;
+        }
+
+        let filled = grid.flood_fill(0, |_, b| !b);
+        assert_eq!(filled.len(), 24);
+        assert_eq!(grid.get(Vec3::new(3, 0, 0)), None);
+    }
+}
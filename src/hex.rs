@@ -0,0 +1,257 @@
+//! Hexagonal grids with pointy-top cells. Positions are axial coordinates
+//! `(q, r)`, where `r` is the row and `q` increases to the east.
+//!
+//! A [`HexGrid`] stores a rectangle of rows in which every odd row is shifted
+//! half a cell to the right, which is how hex maps are usually drawn:
+//!
+//! ```text
+//! a b c
+//!  d e f
+//! g h i
+//! ```
+
+use std::ops::{Add, AddAssign, Mul, Sub};
+
+use itertools::Itertools;
+use strum::{Display, EnumIter, IntoEnumIterator};
+
+use crate::lattice::Lattice;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Hex {
+    pub q: i64,
+    pub r: i64,
+}
+
+impl Hex {
+    pub const ZERO: Self = Self::new(0, 0);
+
+    pub const fn new(q: i64, r: i64) -> Self {
+        Self { q, r }
+    }
+
+    /// The third cube coordinate, so that `q + r + s == 0`
+    pub fn s(self) -> i64 {
+        -self.q - self.r
+    }
+
+    /// Number of steps from the origin
+    pub fn length(self) -> u64 {
+        (self.q.unsigned_abs()
+            + self.r.unsigned_abs()
+            + self.s().unsigned_abs())
+            / 2
+    }
+
+    pub fn neighbors(self) -> impl Iterator<Item = (HexDir, Self)> {
+        HexDir::iter().map(move |dir| (dir, self + dir.into()))
+    }
+}
+
+impl Add for Hex {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        Self::new(self.q + rhs.q, self.r + rhs.r)
+    }
+}
+
+impl Sub for Hex {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        Self::new(self.q - rhs.q, self.r - rhs.r)
+    }
+}
+
+impl Mul<i64> for Hex {
+    type Output = Self;
+    fn mul(self, rhs: i64) -> Self {
+        Self::new(self.q * rhs, self.r * rhs)
+    }
+}
+
+impl AddAssign for Hex {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+/// The six directions between pointy-top hexes, in clockwise order
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, Hash, EnumIter, Display,
+)]
+#[repr(u8)]
+pub enum HexDir {
+    #[default]
+    E,
+    SE,
+    SW,
+    W,
+    NW,
+    NE,
+}
+
+impl HexDir {
+    /// Parse a lowercase direction name such as `"ne"`
+    pub fn from_name(name: &str) -> Option<Self> {
+        HexDir::iter().find(|d| d.to_string().eq_ignore_ascii_case(name))
+    }
+
+    fn rotated(self, steps: u8) -> Self {
+        HexDir::iter()
+            .nth(((self as u8 + steps) % 6) as usize)
+            .unwrap()
+    }
+
+    pub fn opposite(&self) -> Self {
+        self.rotated(3)
+    }
+
+    /// Turn 60° clockwise
+    pub fn turn_right(&self) -> Self {
+        self.rotated(1)
+    }
+
+    /// Turn 60° counter-clockwise
+    pub fn turn_left(&self) -> Self {
+        self.rotated(5)
+    }
+}
+
+impl From<HexDir> for Hex {
+    fn from(value: HexDir) -> Self {
+        match value {
+            HexDir::E => Self::new(1, 0),
+            HexDir::SE => Self::new(0, 1),
+            HexDir::SW => Self::new(-1, 1),
+            HexDir::W => Self::new(-1, 0),
+            HexDir::NW => Self::new(0, -1),
+            HexDir::NE => Self::new(1, -1),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct HexGrid<T> {
+    pub width: usize,
+    pub height: usize,
+    pub data: Vec<T>,
+}
+
+impl<T> HexGrid<T> {
+    pub fn new(data: Vec<T>, width: usize) -> Self {
+        Self {
+            width,
+            height: data.len() / width,
+            data,
+        }
+    }
+
+    /// Axial coordinates of the cell at index `i`
+    pub fn to_hex(&self, i: usize) -> Hex {
+        let (col, row) = ((i % self.width) as i64, (i / self.width) as i64);
+        Hex::new(col - row.div_euclid(2), row)
+    }
+
+    pub fn hex_to_index(&self, hex: Hex) -> Option<usize> {
+        let col = hex.q + hex.r.div_euclid(2);
+
+        ((0..self.width as i64).contains(&col)
+            && (0..self.height as i64).contains(&hex.r))
+        .then(|| col as usize + hex.r as usize * self.width)
+    }
+
+    pub fn get(&self, hex: Hex) -> Option<&'_ T> {
+        self.hex_to_index(hex).map(|i| &self.data[i])
+    }
+
+    pub fn get_mut(&mut self, hex: Hex) -> Option<&'_ mut T> {
+        self.hex_to_index(hex).map(|i| &mut self.data[i])
+    }
+
+    pub fn step_from_index(&self, i: usize, dir: HexDir) -> Option<usize> {
+        self.hex_to_index(self.to_hex(i) + dir.into())
+    }
+}
+
+impl<T> Lattice for HexGrid<T> {
+    type Cell = T;
+    type Dir = HexDir;
+
+    const DIRECTIONS: &'static [HexDir] = &[
+        HexDir::E,
+        HexDir::SE,
+        HexDir::SW,
+        HexDir::W,
+        HexDir::NW,
+        HexDir::NE,
+    ];
+
+    fn cells(&self) -> &[T] {
+        &self.data
+    }
+
+    fn step(&self, i: usize, dir: HexDir) -> Option<usize> {
+        self.step_from_index(i, dir)
+    }
+
+    fn distance(&self, from: usize, to: usize) -> usize {
+        (self.to_hex(to) - self.to_hex(from)).length() as usize
+    }
+
+    /// Rows of cells, with the spaces that offset the rows ignored
+    fn parse(input: &str) -> Self
+    where
+        T: From<char>,
+    {
+        let rows = input
+            .lines()
+            .map(|line| line.chars().filter(|c| *c != ' ').collect_vec())
+            .collect_vec();
+        let width = rows.first().map_or(0, Vec::len);
+
+        debug_assert!(rows.iter().all(|row| row.len() == width));
+
+        Self::new(rows.into_iter().flatten().map(T::from).collect(), width)
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex_coordinates() {
+        let grid: HexGrid<char> = HexGrid::parse("a b c\n d e f\ng h i");
+        assert_eq!((grid.width, grid.height), (3, 3));
+
+        // e sits between b and c in the row above and h and i below
+        let around = grid
+            .neighbors(4)
+            .map(|(_, n)| grid.data[n])
+            .collect::<String>();
+        assert_eq!(around, "fihdbc");
+
+        assert_eq!(grid.to_hex(6), Hex::new(-1, 2));
+        assert_eq!(grid.hex_to_index(Hex::new(-1, 2)), Some(6));
+        assert_eq!(grid.step(0, HexDir::NW), None);
+        assert_eq!(grid.distance(0, 8), 3);
+        assert_eq!(grid.ray(3, HexDir::E).count(), 3);
+    }
+
+    #[test]
+    fn hex_directions() {
+        let path = ["ne", "ne", "s"].map(HexDir::from_name);
+        assert_eq!(path[2], None);
+        assert_eq!(path[0], Some(HexDir::NE));
+
+        let mut hex = Hex::ZERO;
+        for dir in HexDir::iter() {
+            hex += dir.into();
+            assert_eq!(dir.turn_right().turn_left(), dir);
+            assert_eq!(Hex::from(dir) + dir.opposite().into(), Hex::ZERO);
+        }
+        assert_eq!(hex, Hex::ZERO);
+        assert_eq!((Hex::from(HexDir::NE) * 3).length(), 3);
+        assert_eq!(HexDir::W.turn_right(), HexDir::NW);
+    }
+}
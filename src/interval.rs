@@ -0,0 +1,271 @@
+//! Sets of values stored as sorted, disjoint half-open ranges, for puzzles
+//! where the values are too many to store one by one.
+
+use std::ops::Range;
+
+use num_traits::Num;
+
+use crate::intersection;
+
+fn min<T: PartialOrd>(a: T, b: T) -> T {
+    if b < a {
+        b
+    } else {
+        a
+    }
+}
+
+fn max<T: PartialOrd>(a: T, b: T) -> T {
+    if b > a {
+        b
+    } else {
+        a
+    }
+}
+
+/// Split `range` into the parts before, inside and after `by`
+pub fn split<T>(range: &Range<T>, by: &Range<T>) -> [Option<Range<T>>; 3]
+where
+    T: PartialOrd + Copy,
+{
+    let before = range.start..min(range.end, by.start);
+    let after = max(range.start, by.end)..range.end;
+
+    [
+        (before.start < before.end).then_some(before),
+        intersection(range, by),
+        (after.start < after.end).then_some(after),
+    ]
+}
+
+/// A set of values kept as sorted ranges. Ranges never overlap or touch, any
+/// that would are merged.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IntervalSet<T> {
+    ranges: Vec<Range<T>>,
+}
+
+impl<T> Default for IntervalSet<T> {
+    fn default() -> Self {
+        Self { ranges: vec![] }
+    }
+}
+
+impl<T> IntervalSet<T>
+where
+    T: PartialOrd + Copy,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The ranges in ascending order
+    pub fn ranges(&self) -> &[Range<T>] {
+        &self.ranges
+    }
+
+    /// Number of disjoint ranges
+    pub fn len(&self) -> usize {
+        self.ranges.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    pub fn insert(&mut self, range: Range<T>) {
+        if range.start >= range.end {
+            return;
+        }
+
+        let i = self.ranges.partition_point(|r| r.end < range.start);
+        let j = self.ranges.partition_point(|r| r.start <= range.end);
+
+        let merged = if i < j {
+            min(range.start, self.ranges[i].start)
+                ..max(range.end, self.ranges[j - 1].end)
+        } else {
+            range
+        };
+
+        self.ranges.splice(i..j, [merged]);
+    }
+
+    pub fn remove(&mut self, range: Range<T>) {
+        if range.start >= range.end {
+            return;
+        }
+
+        let i = self.ranges.partition_point(|r| r.end <= range.start);
+        let j = self.ranges.partition_point(|r| r.start < range.end);
+
+        if i < j {
+            let [before, _, _] = split(&self.ranges[i], &range);
+            let [_, _, after] = split(&self.ranges[j - 1], &range);
+            self.ranges.splice(i..j, before.into_iter().chain(after));
+        }
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let i = self.ranges.partition_point(|r| r.end <= value);
+        self.ranges.get(i).is_some_and(|r| r.start <= value)
+    }
+
+    /// Whether every value of `range` is in the set
+    pub fn contains_range(&self, range: &Range<T>) -> bool {
+        let i = self.ranges.partition_point(|r| r.end <= range.start);
+
+        range.start >= range.end
+            || self
+                .ranges
+                .get(i)
+                .is_some_and(|r| r.start <= range.start && range.end <= r.end)
+    }
+
+    /// The ranges of the set that share a value with `range`
+    pub fn overlapping(&self, range: &Range<T>) -> &[Range<T>] {
+        let i = self.ranges.partition_point(|r| r.end <= range.start);
+        let j = self.ranges.partition_point(|r| r.start < range.end);
+
+        &self.ranges[i..j.max(i)]
+    }
+
+    pub fn overlaps(&self, range: &Range<T>) -> bool {
+        range.start < range.end && !self.overlapping(range).is_empty()
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut result = self.clone();
+        other.ranges.iter().for_each(|r| result.insert(r.clone()));
+        result
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let ranges = self
+            .ranges
+            .iter()
+            .flat_map(|a| {
+                other
+                    .overlapping(a)
+                    .iter()
+                    .filter_map(move |b| intersection(a, b))
+            })
+            .collect();
+
+        Self { ranges }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut result = self.clone();
+        other.ranges.iter().for_each(|r| result.remove(r.clone()));
+        result
+    }
+}
+
+impl<T> IntervalSet<T>
+where
+    T: Num + PartialOrd + Copy,
+{
+    /// Number of values in the set
+    pub fn total_len(&self) -> T {
+        self.ranges
+            .iter()
+            .fold(T::zero(), |acc, r| acc + (r.end - r.start))
+    }
+
+    /// Map the set through piecewise offsets. Each `(source, dest)` piece
+    /// moves the values in `source` to a range starting at `dest`. Values not
+    /// covered by any piece stay where they are. Pieces must not overlap.
+    pub fn map_through(&self, pieces: &[(Range<T>, T)]) -> Self {
+        let mut unmapped = self.clone();
+        let mut result = Self::new();
+
+        for (source, dest) in pieces {
+            for r in self.overlapping(source) {
+                if let Some(inside) = intersection(r, source) {
+                    unmapped.remove(inside.clone());
+                    result.insert(
+                        inside.start - source.start + *dest
+                            ..inside.end - source.start + *dest,
+                    );
+                }
+            }
+        }
+
+        result.union(&unmapped)
+    }
+}
+
+impl<T> FromIterator<Range<T>> for IntervalSet<T>
+where
+    T: PartialOrd + Copy,
+{
+    fn from_iter<I: IntoIterator<Item = Range<T>>>(iter: I) -> Self {
+        let mut result = Self::new();
+        result.extend(iter);
+        result
+    }
+}
+
+impl<T> Extend<Range<T>> for IntervalSet<T>
+where
+    T: PartialOrd + Copy,
+{
+    fn extend<I: IntoIterator<Item = Range<T>>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|r| self.insert(r));
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insert_and_remove() {
+        let mut set: IntervalSet<i64> =
+            [5..8, 0..2, 1..3, 8..9].into_iter().collect();
+        assert_eq!(set.ranges(), [0..3, 5..9]);
+        assert_eq!(set.total_len(), 7);
+
+        set.remove(2..6);
+        assert_eq!(set.ranges(), [0..2, 6..9]);
+        set.remove(7..8);
+        assert_eq!(set.ranges(), [0..2, 6..7, 8..9]);
+
+        assert!(set.contains(6) && !set.contains(7) && !set.contains(2));
+        assert!(set.contains_range(&(0..2)) && !set.contains_range(&(1..3)));
+        assert!(set.overlaps(&(1..7)) && !set.overlaps(&(2..6)));
+        assert_eq!(set.overlapping(&(1..9)), [0..2, 6..7, 8..9]);
+    }
+
+    #[test]
+    fn set_operations() {
+        let a: IntervalSet<u32> = [0..10, 20..30].into_iter().collect();
+        let b: IntervalSet<u32> = [5..25].into_iter().collect();
+
+        assert_eq!(a.union(&b).ranges(), [0..30]);
+        assert_eq!(a.intersection(&b).ranges(), [5..10, 20..25]);
+        assert_eq!(a.difference(&b).ranges(), [0..5, 25..30]);
+        assert_eq!(b.difference(&a).ranges(), [10..20]);
+    }
+
+    #[test]
+    fn splitting_and_mapping() {
+        assert_eq!(
+            split(&(0..10), &(3..5)),
+            [Some(0..3), Some(3..5), Some(5..10)]
+        );
+        assert_eq!(split(&(0..10), &(12..15)), [Some(0..10), None, None]);
+
+        // the seed to soil map from 2023 day 5
+        let seeds: IntervalSet<u64> = [79..93, 55..68].into_iter().collect();
+        let soil = seeds.map_through(&[(98..100, 50), (50..98, 52)]);
+        assert_eq!(soil.ranges(), [57..70, 81..95]);
+
+        let partial: IntervalSet<u64> = [0..10].into_iter().collect();
+        assert_eq!(
+            partial.map_through(&[(5..7, 100)]).ranges(),
+            [0..5, 7..10, 100..102]
+        );
+    }
+}
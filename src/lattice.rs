@@ -0,0 +1,136 @@
+//! A common interface for grids of any shape. [`Grid`], [`HexGrid`] and
+//! [`Grid3`] all store their cells in a flat `Vec` and step between them by
+//! index, so neighbors, rays, flood fills and searches can be written once.
+//!
+//! [`HexGrid`]: crate::hex::HexGrid
+//! [`Grid3`]: crate::grid3::Grid3
+
+use crate::{Compass, Grid};
+
+pub trait Lattice: Sized {
+    type Cell;
+    type Dir: Copy + 'static;
+
+    /// Directions of the direct neighbors of a cell
+    const DIRECTIONS: &'static [Self::Dir];
+
+    /// All cells in index order
+    fn cells(&self) -> &[Self::Cell];
+
+    /// The index one step from `i` in direction `dir`, if it is on the grid
+    fn step(&self, i: usize, dir: Self::Dir) -> Option<usize>;
+
+    /// Number of steps between two cells if nothing is in the way
+    fn distance(&self, from: usize, to: usize) -> usize;
+
+    /// Parse the grid from text with one character per cell
+    fn parse(input: &str) -> Self
+    where
+        Self::Cell: From<char>;
+
+    fn neighbors(
+        &self,
+        i: usize,
+    ) -> impl Iterator<Item = (Self::Dir, usize)> + '_ {
+        Self::DIRECTIONS
+            .iter()
+            .filter_map(move |dir| self.step(i, *dir).map(|j| (*dir, j)))
+    }
+
+    /// Iterate over the cells from `i` in the direction `dir`, starting with
+    /// `i` itself and stopping before `i` would be visited again
+    fn ray(
+        &self,
+        i: usize,
+        dir: Self::Dir,
+    ) -> impl Iterator<Item = (usize, &'_ Self::Cell)> + '_ {
+        std::iter::successors(Some(i), move |curr| {
+            self.step(*curr, dir).filter(|next| *next != i)
+        })
+        .map(|j| (j, &self.cells()[j]))
+    }
+
+    /// All cells reachable from `start` by stepping to neighbors for which
+    /// `connected(from, to)` holds, in the order they were reached
+    fn flood_fill<F>(&self, start: usize, mut connected: F) -> Vec<usize>
+    where
+        F: FnMut(&Self::Cell, &Self::Cell) -> bool,
+    {
+        let cells = self.cells();
+        let mut seen = vec![false; cells.len()];
+        let mut stack = vec![start];
+        let mut result = vec![];
+        seen[start] = true;
+
+        while let Some(curr) = stack.pop() {
+            result.push(curr);
+
+            for (_, n) in Lattice::neighbors(self, curr) {
+                if !seen[n] && connected(&cells[curr], &cells[n]) {
+                    seen[n] = true;
+                    stack.push(n);
+                }
+            }
+        }
+
+        result
+    }
+}
+
+impl<T> Lattice for Grid<T> {
+    type Cell = T;
+    type Dir = Compass;
+
+    const DIRECTIONS: &'static [Compass] =
+        &[Compass::N, Compass::E, Compass::S, Compass::W];
+
+    fn cells(&self) -> &[T] {
+        &self.data
+    }
+
+    fn step(&self, i: usize, dir: Compass) -> Option<usize> {
+        self.step_from_index(i, dir)
+    }
+
+    fn distance(&self, from: usize, to: usize) -> usize {
+        self.min_dist(from, to)
+    }
+
+    fn parse(input: &str) -> Self
+    where
+        T: From<char>,
+    {
+        Self::parse_lines(input)
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::*;
+
+    /// Count the cells connected to `start` through equal values, using only
+    /// the trait so it works for any lattice
+    fn component_size<L>(lattice: &L, start: usize) -> usize
+    where
+        L: Lattice,
+        L::Cell: PartialEq,
+    {
+        lattice.flood_fill(start, |a, b| a == b).len()
+    }
+
+    #[test]
+    fn grid_lattice() {
+        let grid: Grid<char> = Lattice::parse("aab\nbab\nbbb");
+
+        assert_eq!(component_size(&grid, 0), 3);
+        assert_eq!(component_size(&grid, 2), 6);
+        assert_eq!(Lattice::neighbors(&grid, 0).count(), 2);
+        assert_eq!(
+            Lattice::ray(&grid, 0, Compass::S)
+                .map(|(_, c)| *c)
+                .collect::<String>(),
+            "abb"
+        );
+        assert_eq!(grid.distance(0, 8), 4);
+    }
+}
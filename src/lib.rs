@@ -1,7 +1,31 @@
+extern crate self as advent_of_code;
+
+pub mod cycle;
 mod day;
+pub mod generate;
+pub mod graph;
+pub mod grid3;
+pub mod hex;
+pub mod interval;
+pub mod lattice;
+pub mod math;
+pub mod memo;
+pub mod parse;
+pub mod region;
+pub mod render;
+pub mod rng;
+pub mod search;
 pub mod template;
+mod transform;
+pub mod visualize;
 
-use std::ops::Range;
+use std::{
+    collections::HashMap,
+    ops::{
+        Add, AddAssign, BitAnd, BitOr, BitXor, Index, IndexMut, Mul, Neg, Not,
+        Range, Sub, SubAssign,
+    },
+};
 
 pub use day::*;
 
@@ -48,7 +72,7 @@ where
 
     let min_end = if r1.end < r2.end { r1.end } else { r2.end };
 
-    (max_start < min_end).then(|| max_start..min_end)
+    (max_start < min_end).then_some(max_start..min_end)
 }
 
 #[derive(
@@ -66,6 +90,7 @@ pub enum Compass {
 #[derive(
     Debug, Default, Clone, Copy, PartialEq, Eq, Hash, EnumIter, Display,
 )]
+#[repr(u8)]
 pub enum FullCompass {
     #[default]
     N,
@@ -89,6 +114,30 @@ impl From<Compass> for FullCompass {
     }
 }
 
+/// The change from one heading to another
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, Hash, EnumIter, Display,
+)]
+#[repr(u8)]
+pub enum Turn {
+    #[default]
+    Straight,
+    Right,
+    Back,
+    Left,
+}
+
+impl Turn {
+    /// Number of 90° turns needed, turning whichever way is shorter
+    pub fn quarter_turns(self) -> usize {
+        match self {
+            Turn::Straight => 0,
+            Turn::Right | Turn::Left => 1,
+            Turn::Back => 2,
+        }
+    }
+}
+
 impl Compass {
     pub fn from_relative(relative: char) -> Option<Self> {
         match relative {
@@ -100,35 +149,70 @@ impl Compass {
         }
     }
 
-    pub fn opposite(&self) -> Self {
+    pub fn to_relative(&self) -> char {
         use Compass as D;
-
         match self {
-            D::E => D::W,
-            D::N => D::S,
-            D::W => D::E,
-            D::S => D::N,
+            D::N => 'U',
+            D::E => 'R',
+            D::S => 'D',
+            D::W => 'L',
         }
     }
 
-    pub fn turn_right(&self) -> Self {
+    pub fn from_arrow(arrow: char) -> Option<Self> {
+        Compass::iter().find(|d| char::from(d.to_arrow()) == arrow)
+    }
+
+    pub fn from_letter(letter: char) -> Option<Self> {
+        Compass::iter().find(|d| d.to_letter() == letter)
+    }
+
+    pub fn to_letter(&self) -> char {
         use Compass as D;
         match self {
-            D::E => D::S,
-            D::N => D::E,
-            D::W => D::N,
-            D::S => D::W,
+            D::N => 'N',
+            D::E => 'E',
+            D::S => 'S',
+            D::W => 'W',
         }
     }
 
+    /// Position in clockwise order starting from north, for use as an array
+    /// index
+    pub fn index(&self) -> usize {
+        *self as usize
+    }
+
+    pub fn from_index(i: usize) -> Self {
+        Compass::iter().nth(i % 4).unwrap()
+    }
+
+    /// Rotate by `steps` times 90°, clockwise for positive `steps`
+    pub fn rotate(&self, steps: i32) -> Self {
+        Self::from_index((self.index() as i32 + steps).rem_euclid(4) as usize)
+    }
+
+    pub fn turn(&self, turn: Turn) -> Self {
+        self.rotate(turn as i32)
+    }
+
+    /// The turn that makes `self` face `other`
+    pub fn turn_to(&self, other: Compass) -> Turn {
+        Turn::iter()
+            .nth((other.index() + 4 - self.index()) % 4)
+            .unwrap()
+    }
+
+    pub fn opposite(&self) -> Self {
+        self.rotate(2)
+    }
+
+    pub fn turn_right(&self) -> Self {
+        self.rotate(1)
+    }
+
     pub fn turn_left(&self) -> Self {
-        use Compass as D;
-        match self {
-            D::E => D::N,
-            D::N => D::W,
-            D::W => D::S,
-            D::S => D::E,
-        }
+        self.rotate(-1)
     }
 
     pub fn to_arrow(&self) -> u8 {
@@ -142,11 +226,319 @@ impl Compass {
     }
 }
 
+/// Accepts arrows (`^>v<`), letters (`NESW`) and relative moves (`UDLR`)
+impl TryFrom<char> for Compass {
+    type Error = char;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        Self::from_arrow(c)
+            .or_else(|| Self::from_letter(c))
+            .or_else(|| Self::from_relative(c))
+            .ok_or(c)
+    }
+}
+
+impl TryFrom<FullCompass> for Compass {
+    type Error = FullCompass;
+
+    fn try_from(value: FullCompass) -> Result<Self, Self::Error> {
+        Compass::iter()
+            .find(|d| FullCompass::from(*d) == value)
+            .ok_or(value)
+    }
+}
+
+impl FullCompass {
+    /// Parse a direction name such as `"NE"`, ignoring case
+    pub fn from_name(name: &str) -> Option<Self> {
+        FullCompass::iter().find(|d| d.to_string().eq_ignore_ascii_case(name))
+    }
+
+    pub fn is_diagonal(&self) -> bool {
+        Compass::try_from(*self).is_err()
+    }
+
+    /// Rotate by `steps` times 45°, clockwise for positive `steps`
+    pub fn rotate(&self, steps: i32) -> Self {
+        let i = (*self as i32 + steps).rem_euclid(8) as usize;
+        FullCompass::iter().nth(i).unwrap()
+    }
+
+    pub fn opposite(&self) -> Self {
+        self.rotate(4)
+    }
+
+    /// Turn 45° clockwise
+    pub fn turn_right(&self) -> Self {
+        self.rotate(1)
+    }
+
+    /// Turn 45° counter-clockwise
+    pub fn turn_left(&self) -> Self {
+        self.rotate(-1)
+    }
+}
+
+/// A value for each [`Compass`] direction, stored inline and indexed by the
+/// direction
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DirMap<T>(pub [T; 4]);
+
+impl<T> DirMap<T> {
+    pub fn from_fn<F>(mut f: F) -> Self
+    where
+        F: FnMut(Compass) -> T,
+    {
+        Self(std::array::from_fn(|i| f(Compass::from_index(i))))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Compass, &'_ T)> {
+        Compass::iter().zip(&self.0)
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Compass, &'_ mut T)> {
+        Compass::iter().zip(&mut self.0)
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &'_ T> {
+        self.0.iter()
+    }
+
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &'_ mut T> {
+        self.0.iter_mut()
+    }
+}
+
+impl<T> Index<Compass> for DirMap<T> {
+    type Output = T;
+    fn index(&self, dir: Compass) -> &T {
+        &self.0[dir.index()]
+    }
+}
+
+impl<T> IndexMut<Compass> for DirMap<T> {
+    fn index_mut(&mut self, dir: Compass) -> &mut T {
+        &mut self.0[dir.index()]
+    }
+}
+
+/// A signed 2D vector used both for positions and offsets on a grid. `x` is
+/// the column and `y` the row, so north is negative `y`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Vec2 {
+    pub x: i64,
+    pub y: i64,
+}
+
+/// A position on a grid. Positions can lie off the grid
+pub type Pos = Vec2;
+
+impl Vec2 {
+    pub const ZERO: Self = Self::new(0, 0);
+
+    pub const fn new(x: i64, y: i64) -> Self {
+        Self { x, y }
+    }
+
+    pub fn manhattan(self) -> u64 {
+        self.x.unsigned_abs() + self.y.unsigned_abs()
+    }
+
+    pub fn signum(self) -> Self {
+        Self::new(self.x.signum(), self.y.signum())
+    }
+
+    /// Component-wise euclidean remainder, used to wrap around a `size` area
+    pub fn rem_euclid(self, size: Self) -> Self {
+        Self::new(self.x.rem_euclid(size.x), self.y.rem_euclid(size.y))
+    }
+
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        Some(Self::new(
+            self.x.checked_add(rhs.x)?,
+            self.y.checked_add(rhs.y)?,
+        ))
+    }
+
+    pub fn checked_mul(self, rhs: i64) -> Option<Self> {
+        Some(Self::new(
+            self.x.checked_mul(rhs)?,
+            self.y.checked_mul(rhs)?,
+        ))
+    }
+
+    /// Rotate 90° clockwise on screen (north becomes east)
+    pub fn rotate_right(self) -> Self {
+        Self::new(-self.y, self.x)
+    }
+
+    /// Rotate 90° counter-clockwise on screen (north becomes west)
+    pub fn rotate_left(self) -> Self {
+        Self::new(self.y, -self.x)
+    }
+
+    /// Get the neighboring positions in all the `Compass` directions
+    pub fn neighbors(self) -> impl Iterator<Item = (Compass, Self)> {
+        Compass::iter().map(move |dir| (dir, self + dir.into()))
+    }
+}
+
+impl From<(i64, i64)> for Vec2 {
+    fn from((x, y): (i64, i64)) -> Self {
+        Self::new(x, y)
+    }
+}
+
+impl From<Vec2> for (i64, i64) {
+    fn from(value: Vec2) -> Self {
+        (value.x, value.y)
+    }
+}
+
+impl From<Compass> for Vec2 {
+    fn from(value: Compass) -> Self {
+        FullCompass::from(value).into()
+    }
+}
+
+impl From<FullCompass> for Vec2 {
+    fn from(value: FullCompass) -> Self {
+        use FullCompass as D;
+
+        match value {
+            D::N => Self::new(0, -1),
+            D::NE => Self::new(1, -1),
+            D::E => Self::new(1, 0),
+            D::SE => Self::new(1, 1),
+            D::S => Self::new(0, 1),
+            D::SW => Self::new(-1, 1),
+            D::W => Self::new(-1, 0),
+            D::NW => Self::new(-1, -1),
+        }
+    }
+}
+
+impl Compass {
+    /// Get the direction of a unit vector
+    pub fn from_vec(v: Vec2) -> Option<Self> {
+        Compass::iter().find(|d| Vec2::from(*d) == v)
+    }
+}
+
+impl FullCompass {
+    /// Get the direction of a unit vector (including diagonals)
+    pub fn from_vec(v: Vec2) -> Option<Self> {
+        FullCompass::iter().find(|d| Vec2::from(*d) == v)
+    }
+}
+
+impl Add for Vec2 {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        Self::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl Sub for Vec2 {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        Self::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl Neg for Vec2 {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self::new(-self.x, -self.y)
+    }
+}
+
+impl Mul<i64> for Vec2 {
+    type Output = Self;
+    fn mul(self, rhs: i64) -> Self {
+        Self::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+impl AddAssign for Vec2 {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for Vec2 {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+/// How the edges of a [`Grid`] behave. Stepping off a wrapping axis enters
+/// the grid again from the opposite edge, so a grid wrapping on both axes is
+/// a torus.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Topology {
+    pub wrap_x: bool,
+    pub wrap_y: bool,
+}
+
+impl Topology {
+    pub const BOUNDED: Self = Self {
+        wrap_x: false,
+        wrap_y: false,
+    };
+    pub const TORUS: Self = Self {
+        wrap_x: true,
+        wrap_y: true,
+    };
+
+    /// Bring the coordinate `v` onto an axis of length `len`
+    fn wrap_axis(v: i64, len: usize, wrap: bool) -> Option<i64> {
+        if wrap {
+            Some(v.rem_euclid(len as i64))
+        } else {
+            (0..len as i64).contains(&v).then_some(v)
+        }
+    }
+
+    /// Step `i` by `d` along an axis of length `len`
+    fn step_axis(i: usize, d: i64, len: usize, wrap: bool) -> Option<usize> {
+        Self::wrap_axis(i as i64 + d, len, wrap).map(|v| v as usize)
+    }
+
+    /// Step the index `i` of a `width` by `height` grid in direction `dir`
+    fn step(
+        self,
+        width: usize,
+        height: usize,
+        i: usize,
+        dir: FullCompass,
+    ) -> Option<usize> {
+        let d = Vec2::from(dir);
+
+        let col = Self::step_axis(i % width, d.x, width, self.wrap_x)?;
+        let row = Self::step_axis(i / width, d.y, height, self.wrap_y)?;
+
+        Some(col + row * width)
+    }
+
+    /// Distance between two coordinates along an axis of length `len`
+    fn axis_dist(a: usize, b: usize, len: usize, wrap: bool) -> usize {
+        let dist = a.abs_diff(b);
+
+        if wrap {
+            dist.min(len - dist)
+        } else {
+            dist
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Grid<T> {
     pub width: usize,
     pub height: usize,
     pub data: Vec<T>,
+    pub topology: Topology,
 }
 
 impl<T> Grid<T>
@@ -167,6 +559,149 @@ where
 
         Self::new(data, width)
     }
+
+    /// Parse several grids separated by blank lines
+    pub fn parse_many(input: &str) -> Vec<Self> {
+        input
+            .trim_end()
+            .split("\n\n")
+            .map(Self::parse_lines)
+            .collect()
+    }
+}
+
+/// Indices of the marker characters taken out by [`Grid::parse_with_markers`]
+pub type Markers = HashMap<char, Vec<usize>>;
+
+/// Why [`Grid::try_parse_lines`] failed. Rows and columns are 0-based.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GridParseError<E> {
+    Empty,
+    /// A line is not as long as the first one
+    Width {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// A character could not be converted into a cell
+    Cell {
+        pos: Pos,
+        c: char,
+        error: E,
+    },
+}
+
+impl<E: std::fmt::Debug> std::fmt::Display for GridParseError<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Empty => write!(f, "empty grid"),
+            Self::Width {
+                row,
+                expected,
+                found,
+            } => write!(f, "row {row} has width {found}, expected {expected}"),
+            Self::Cell { pos, c, error } => write!(
+                f,
+                "invalid cell {c:?} at row {}, column {}: {error:?}",
+                pos.y, pos.x
+            ),
+        }
+    }
+}
+
+impl<E: std::fmt::Debug> std::error::Error for GridParseError<E> {}
+
+impl<T> Grid<T>
+where
+    T: TryFrom<char>,
+{
+    /// Parse a grid, checking that all lines have the same width and that
+    /// every character converts into a cell
+    pub fn try_parse_lines(
+        input: &str,
+    ) -> Result<Self, GridParseError<T::Error>> {
+        Self::parse_cells(input, None::<(&str, fn(char) -> T)>)
+            .map(|(grid, _)| grid)
+    }
+
+    /// Parse several grids separated by blank lines. On failure, also returns
+    /// which grid failed.
+    pub fn try_parse_many(
+        input: &str,
+    ) -> Result<Vec<Self>, (usize, GridParseError<T::Error>)> {
+        input
+            .trim_end()
+            .split("\n\n")
+            .enumerate()
+            .map(|(i, grid)| Self::try_parse_lines(grid).map_err(|e| (i, e)))
+            .collect()
+    }
+
+    /// Parse a grid, taking out the `markers` (like `S`, `E` or `@`) and
+    /// putting `floor(marker)` in their place. Returns the indices of each
+    /// marker found.
+    pub fn parse_with_markers<F>(
+        input: &str,
+        markers: &str,
+        floor: F,
+    ) -> Result<(Self, Markers), GridParseError<T::Error>>
+    where
+        F: FnMut(char) -> T,
+    {
+        Self::parse_cells(input, Some((markers, floor)))
+    }
+
+    /// Parse a grid, replacing the given markers with `floor` if there are
+    /// any
+    fn parse_cells<F>(
+        input: &str,
+        mut markers: Option<(&str, F)>,
+    ) -> Result<(Self, Markers), GridParseError<T::Error>>
+    where
+        F: FnMut(char) -> T,
+    {
+        let width = input.lines().next().map_or(0, |l| l.chars().count());
+        let mut found = Markers::new();
+        let mut data = Vec::with_capacity(input.len());
+
+        if width == 0 {
+            return Err(GridParseError::Empty);
+        }
+
+        for (row, line) in input.lines().enumerate() {
+            let mut count = 0;
+
+            for (col, c) in line.chars().enumerate() {
+                count += 1;
+
+                if let Some((markers, floor)) = &mut markers {
+                    if markers.contains(c) {
+                        found.entry(c).or_default().push(data.len());
+                        data.push(floor(c));
+                        continue;
+                    }
+                }
+
+                let cell =
+                    T::try_from(c).map_err(|error| GridParseError::Cell {
+                        pos: Pos::new(col as i64, row as i64),
+                        c,
+                        error,
+                    })?;
+                data.push(cell);
+            }
+
+            if count != width {
+                return Err(GridParseError::Width {
+                    row,
+                    expected: width,
+                    found: count,
+                });
+            }
+        }
+
+        Ok((Self::new(data, width), found))
+    }
 }
 
 impl<T> Grid<T>
@@ -175,12 +710,7 @@ where
     T: Copy,
 {
     pub fn print(&self) {
-        self.rows().for_each(|row| {
-            println!(
-                "{}",
-                row.iter().map(|t| char::from(*t)).collect::<String>()
-            )
-        });
+        print!("{}", self.renderer(|t| char::from(*t)));
     }
 }
 
@@ -191,9 +721,14 @@ impl<T> Grid<T> {
             data,
             width,
             height,
+            topology: Topology::BOUNDED,
         }
     }
 
+    pub fn with_topology(self, topology: Topology) -> Self {
+        Self { topology, ..self }
+    }
+
     pub fn rows(&self) -> impl DoubleEndedIterator<Item = &'_ [T]> + '_ {
         self.data.chunks(self.width)
     }
@@ -221,31 +756,11 @@ impl<T> Grid<T> {
         self.data.get(i)
     }
 
-    #[allow(clippy::unnecessary_lazy_evaluations)]
     pub fn step_from_index<D>(&self, i: usize, dir: D) -> Option<usize>
     where
         FullCompass: From<D>,
     {
-        use FullCompass as D;
-
-        let (col, row) = self.to_col_row(i);
-        let max_row = self.height - 1;
-        let max_col = self.width - 1;
-
-        let res_col_row = match FullCompass::from(dir) {
-            D::N => (row > 0).then(|| (col, row - 1)),
-            D::NE => (row > 0 && col < max_col).then(|| (col + 1, row - 1)),
-            D::E => (col < max_col).then(|| (col + 1, row)),
-            D::SE => {
-                (row < max_row && col < max_col).then(|| (col + 1, row + 1))
-            }
-            D::S => (row < max_row).then(|| (col, row + 1)),
-            D::SW => (row < max_row && col > 0).then(|| (col - 1, row + 1)),
-            D::W => (col > 0).then(|| (col - 1, row)),
-            D::NW => (row > 0 && col > 0).then(|| (col - 1, row - 1)),
-        };
-
-        res_col_row.map(|(col, row)| self.to_index(col, row))
+        self.topology.step(self.width, self.height, i, dir.into())
     }
 
     pub fn neighbors(
@@ -262,32 +777,47 @@ impl<T> Grid<T> {
             .filter(move |dir| self.step_from_index(i, *dir).is_some())
     }
 
+    /// Manhattan distance between two cells, going around the edges of
+    /// wrapping axes if that is shorter
     pub fn min_dist(&self, from: usize, to: usize) -> usize {
-        let col_dist = (from % self.width).abs_diff(to % self.width);
-        let row_dist = (from / self.width).abs_diff(to / self.width);
+        let Topology { wrap_x, wrap_y } = self.topology;
+        let (c1, r1) = self.to_col_row(from);
+        let (c2, r2) = self.to_col_row(to);
 
-        col_dist + row_dist
+        Topology::axis_dist(c1, c2, self.width, wrap_x)
+            + Topology::axis_dist(r1, r2, self.height, wrap_y)
     }
 
+    /// Whether the cell is on an edge of the grid. Wrapping axes have no
+    /// edges.
     pub fn is_border(&self, i: usize) -> bool {
-        let col = i % self.width;
-        let row = i / self.width;
+        let Topology { wrap_x, wrap_y } = self.topology;
+        let (col, row) = self.to_col_row(i);
 
-        row == 0 || col == 0 || row == self.height - 1 || col == self.width - 1
+        (!wrap_y && (row == 0 || row == self.height - 1))
+            || (!wrap_x && (col == 0 || col == self.width - 1))
     }
 
+    /// The cells on the edges of the grid. Wrapping axes have no edges, so a
+    /// torus has no border at all.
     pub fn border(&self) -> impl Iterator<Item = usize> + '_ {
-        (0..self.width)
-            .chain((self.data.len() - self.width)..self.data.len())
-            .chain((0..self.height).map(|i| i * self.width))
-            .chain((0..self.height).map(|i| (i + 1) * self.width - 1))
+        let Topology { wrap_x, wrap_y } = self.topology;
+
+        let rows = (!wrap_y).then(|| {
+            (0..self.width)
+                .chain((self.data.len() - self.width)..self.data.len())
+        });
+        let cols = (!wrap_x).then(|| {
+            (0..self.height)
+                .map(|i| i * self.width)
+                .chain((0..self.height).map(|i| (i + 1) * self.width - 1))
+        });
+
+        rows.into_iter().flatten().chain(cols.into_iter().flatten())
     }
 
     pub fn are_neighbors(&self, i1: usize, i2: usize) -> bool {
-        let (r1, c1) = (i1 / self.width, i1 % self.width);
-        let (r2, c2) = (i2 / self.width, i2 % self.width);
-
-        r1.abs_diff(r2) + c1.abs_diff(c2) == 1
+        self.min_dist(i1, i2) == 1
     }
 
     pub fn corners(&self) -> impl Iterator<Item = usize> {
@@ -308,10 +838,61 @@ impl<T> Grid<T> {
         col + row * self.width
     }
 
+    pub fn to_pos(&self, i: usize) -> Pos {
+        let (col, row) = self.to_col_row(i);
+        Pos::new(col as i64, row as i64)
+    }
+
+    /// Get the size of the grid as a vector from the first to one past the
+    /// last cell
+    pub fn size(&self) -> Vec2 {
+        Vec2::new(self.width as i64, self.height as i64)
+    }
+
+    pub fn contains(&self, pos: Pos) -> bool {
+        (0..self.width as i64).contains(&pos.x)
+            && (0..self.height as i64).contains(&pos.y)
+    }
+
+    /// Get the index of the given position if it's on the grid
+    pub fn pos_to_index(&self, pos: Pos) -> Option<usize> {
+        self.contains(pos)
+            .then(|| self.to_index(pos.x as usize, pos.y as usize))
+    }
+
+    /// Map any position onto the grid by wrapping around the edges
+    pub fn wrap(&self, pos: Pos) -> Pos {
+        pos.rem_euclid(self.size())
+    }
+
+    /// Move `pos` by `offset`, which may be longer than one cell, following
+    /// the topology. `None` if it ends up off a bounded axis.
+    pub fn translate(&self, pos: Pos, offset: Vec2) -> Option<Pos> {
+        let Topology { wrap_x, wrap_y } = self.topology;
+        let moved = pos + offset;
+
+        Some(Pos::new(
+            Topology::wrap_axis(moved.x, self.width, wrap_x)?,
+            Topology::wrap_axis(moved.y, self.height, wrap_y)?,
+        ))
+    }
+
+    pub fn get(&self, pos: Pos) -> Option<&'_ T> {
+        self.pos_to_index(pos).map(|i| &self.data[i])
+    }
+
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&'_ mut T> {
+        self.pos_to_index(pos).map(|i| &mut self.data[i])
+    }
+
+    /// Iterate over the cells from `index` in the direction `dir`, starting
+    /// with `index` itself. On wrapping axes the ray stops before it would
+    /// visit `index` a second time.
     pub fn ray(&self, index: usize, dir: FullCompass) -> RayIter<'_, T> {
         RayIter {
             grid: self,
             dir,
+            start: index,
             curr: Some(index),
         }
     }
@@ -324,6 +905,7 @@ impl<T> Grid<T> {
             data,
             height,
             width,
+            topology,
         } = self;
 
         let data = data.into_iter().map(f).collect_vec();
@@ -332,6 +914,7 @@ impl<T> Grid<T> {
             data,
             width,
             height,
+            topology,
         }
     }
 }
@@ -339,6 +922,7 @@ impl<T> Grid<T> {
 pub struct RayIter<'a, T> {
     grid: &'a Grid<T>,
     dir: FullCompass,
+    start: usize,
     curr: Option<usize>,
 }
 
@@ -349,7 +933,10 @@ impl<'a, T> Iterator for RayIter<'a, T> {
 
         let curr = result?;
 
-        self.curr = self.grid.step_from_index(curr, self.dir);
+        self.curr = self
+            .grid
+            .step_from_index(curr, self.dir)
+            .filter(|next| *next != self.start);
 
         result.map(|i| (i, &self.grid.data[i]))
     }
@@ -360,26 +947,841 @@ where
     T: Copy,
 {
     pub fn quadruple(self) -> Self {
-        let mut data = Vec::with_capacity(self.data.len() * 4);
-        let width = self.width * 2;
-        let height = self.height * 2;
-
-        for _ in 0..2 {
-            for row in 0..self.height {
-                for _ in 0..2 {
-                    data.extend(
-                        self.data[(row * self.width)..((row + 1) * self.width)]
-                            .iter()
-                            .copied(),
-                    );
-                }
-            }
-        }
+        self.tile(2, 2)
+    }
+}
 
+/// A grid without fixed bounds that only stores occupied cells. Positions
+/// are signed and may grow in any direction. The bounding box of the occupied
+/// cells is kept up to date.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Pos, T>,
+    bounds: Option<(Pos, Pos)>,
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
         Self {
-            data,
-            height,
-            width,
+            cells: HashMap::new(),
+            bounds: None,
         }
     }
 }
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn insert(&mut self, pos: Pos, value: T) -> Option<T> {
+        self.bounds = Some(match self.bounds {
+            None => (pos, pos),
+            Some((min, max)) => (
+                Pos::new(min.x.min(pos.x), min.y.min(pos.y)),
+                Pos::new(max.x.max(pos.x), max.y.max(pos.y)),
+            ),
+        });
+
+        self.cells.insert(pos, value)
+    }
+
+    pub fn remove(&mut self, pos: Pos) -> Option<T> {
+        let result = self.cells.remove(&pos)?;
+
+        // Only cells on the edge of the bounding box can shrink it
+        let on_edge = self.bounds.is_some_and(|(min, max)| {
+            pos.x == min.x || pos.y == min.y || pos.x == max.x || pos.y == max.y
+        });
+
+        if on_edge {
+            self.bounds = Self::compute_bounds(self.cells.keys());
+        }
+
+        Some(result)
+    }
+
+    fn compute_bounds<'a>(
+        positions: impl Iterator<Item = &'a Pos>,
+    ) -> Option<(Pos, Pos)> {
+        positions.fold(None, |acc, p| {
+            Some(match acc {
+                None => (*p, *p),
+                Some((min, max)) => (
+                    Pos::new(min.x.min(p.x), min.y.min(p.y)),
+                    Pos::new(max.x.max(p.x), max.y.max(p.y)),
+                ),
+            })
+        })
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.clear();
+        self.bounds = None;
+    }
+
+    pub fn get(&self, pos: Pos) -> Option<&'_ T> {
+        self.cells.get(&pos)
+    }
+
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&'_ mut T> {
+        self.cells.get_mut(&pos)
+    }
+
+    pub fn contains(&self, pos: Pos) -> bool {
+        self.cells.contains_key(&pos)
+    }
+
+    /// Get the smallest and largest occupied positions (both inclusive)
+    pub fn bounding_box(&self) -> Option<(Pos, Pos)> {
+        self.bounds
+    }
+
+    /// Check if the position lies within the bounding box
+    pub fn in_bounds(&self, pos: Pos) -> bool {
+        self.bounds.is_some_and(|(min, max)| {
+            (min.x..=max.x).contains(&pos.x) && (min.y..=max.y).contains(&pos.y)
+        })
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &'_ T)> + '_ {
+        self.cells.iter().map(|(p, t)| (*p, t))
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = Pos> + '_ {
+        self.cells.keys().copied()
+    }
+
+    pub fn step<D>(&self, pos: Pos, dir: D) -> Pos
+    where
+        Vec2: From<D>,
+    {
+        pos + Vec2::from(dir)
+    }
+
+    /// Get all four neighbors of the position. There are no edges, so every
+    /// direction has one
+    pub fn neighbors(
+        &self,
+        pos: Pos,
+    ) -> impl Iterator<Item = (Compass, Pos)> + '_ {
+        pos.neighbors()
+    }
+
+    /// Get the neighbors of the position that hold a value
+    pub fn occupied_neighbors(
+        &self,
+        pos: Pos,
+    ) -> impl Iterator<Item = (Compass, Pos, &'_ T)> + '_ {
+        pos.neighbors()
+            .filter_map(|(d, n)| self.get(n).map(|t| (d, n, t)))
+    }
+
+    /// Walk from `pos` in the given direction until leaving the bounding box
+    pub fn ray(&self, pos: Pos, dir: FullCompass) -> SparseRayIter<'_, T> {
+        SparseRayIter {
+            grid: self,
+            step: dir.into(),
+            curr: self.in_bounds(pos).then_some(pos),
+        }
+    }
+
+    pub fn map<F, U>(self, mut f: F) -> SparseGrid<U>
+    where
+        F: FnMut(T) -> U,
+    {
+        SparseGrid {
+            cells: self.cells.into_iter().map(|(p, t)| (p, f(t))).collect(),
+            bounds: self.bounds,
+        }
+    }
+
+    /// Render the bounding box row by row, using `empty` for unoccupied cells
+    pub fn render(&self, empty: char, mut f: impl FnMut(&T) -> char) -> String {
+        let Some((min, max)) = self.bounds else {
+            return String::new();
+        };
+
+        (min.y..=max.y)
+            .map(|y| {
+                (min.x..=max.x)
+                    .map(|x| self.get(Pos::new(x, y)).map_or(empty, &mut f))
+                    .collect::<String>()
+            })
+            .join("\n")
+    }
+}
+
+impl<T> SparseGrid<T>
+where
+    T: Clone,
+{
+    /// Convert the bounding box into a dense grid with `fill` in the
+    /// unoccupied cells. Also returns the position of the grid's first cell
+    pub fn to_grid(&self, fill: T) -> Option<(Grid<T>, Pos)> {
+        let (min, max) = self.bounds?;
+        let width = (max.x - min.x + 1) as usize;
+        let height = (max.y - min.y + 1) as usize;
+        let mut grid = Grid::new(vec![fill; width * height], width);
+
+        self.iter().for_each(|(p, t)| {
+            let i =
+                grid.to_index((p.x - min.x) as usize, (p.y - min.y) as usize);
+            grid.data[i] = t.clone();
+        });
+
+        Some((grid, min))
+    }
+}
+
+impl<T> SparseGrid<T>
+where
+    char: From<T>,
+    T: Copy,
+{
+    pub fn print(&self) {
+        println!("{}", self.render('.', |t| char::from(*t)));
+    }
+}
+
+impl<T> SparseGrid<T> {
+    /// Convert a dense grid, keeping only the cells matching `keep`
+    pub fn from_grid_filtered<F>(grid: Grid<T>, mut keep: F) -> Self
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let width = grid.width;
+
+        grid.data
+            .into_iter()
+            .enumerate()
+            .filter(|(_, t)| keep(t))
+            .map(|(i, t)| (Pos::new((i % width) as i64, (i / width) as i64), t))
+            .collect()
+    }
+}
+
+impl<T> From<Grid<T>> for SparseGrid<T> {
+    fn from(grid: Grid<T>) -> Self {
+        Self::from_grid_filtered(grid, |_| true)
+    }
+}
+
+impl<T> FromIterator<(Pos, T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = (Pos, T)>>(iter: I) -> Self {
+        let mut result = Self::new();
+        result.extend(iter);
+        result
+    }
+}
+
+impl<T> Extend<(Pos, T)> for SparseGrid<T> {
+    fn extend<I: IntoIterator<Item = (Pos, T)>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|(p, t)| {
+            self.insert(p, t);
+        });
+    }
+}
+
+pub struct SparseRayIter<'a, T> {
+    grid: &'a SparseGrid<T>,
+    step: Vec2,
+    curr: Option<Pos>,
+}
+
+impl<'a, T> Iterator for SparseRayIter<'a, T> {
+    type Item = (Pos, Option<&'a T>);
+    fn next(&mut self) -> Option<Self::Item> {
+        let curr = self.curr?;
+        let next = curr + self.step;
+
+        self.curr = self.grid.in_bounds(next).then_some(next);
+
+        Some((curr, self.grid.get(curr)))
+    }
+}
+
+/// A grid of booleans packed 64 cells to a `u64`. Every row starts on a new
+/// word, so rows can be shifted and combined a word at a time. Cells are
+/// indexed like in [`Grid`] and the grid is always bounded.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitGrid {
+    pub width: usize,
+    pub height: usize,
+    /// Words per row
+    stride: usize,
+    words: Vec<u64>,
+}
+
+impl BitGrid {
+    /// Offsets of the 4 orthogonal neighbors, followed by the 4 diagonal ones
+    const OFFSETS: [(i64, i64); 8] = [
+        (0, -1),
+        (1, 0),
+        (0, 1),
+        (-1, 0),
+        (1, -1),
+        (1, 1),
+        (-1, 1),
+        (-1, -1),
+    ];
+
+    pub fn new(width: usize, height: usize) -> Self {
+        let stride = width.div_ceil(64);
+
+        Self {
+            width,
+            height,
+            stride,
+            words: vec![0; stride * height],
+        }
+    }
+
+    /// Set the cells of `grid` that match `pred`
+    pub fn from_grid<T, F>(grid: &Grid<T>, pred: F) -> Self
+    where
+        F: Fn(&T) -> bool,
+    {
+        let mut result = Self::new(grid.width, grid.height);
+
+        grid.data
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| pred(t))
+            .for_each(|(i, _)| result.set(i, true));
+
+        result
+    }
+
+    /// The word holding cell `i` and the cell's bit in it
+    fn bit(&self, i: usize) -> (usize, u64) {
+        let (col, row) = self.to_col_row(i);
+        (row * self.stride + col / 64, 1 << (col % 64))
+    }
+
+    /// Mask of the bits in the last word of a row that are on the grid
+    fn tail_mask(&self) -> u64 {
+        match self.width % 64 {
+            0 => u64::MAX,
+            n => (1 << n) - 1,
+        }
+    }
+
+    pub fn get(&self, i: usize) -> bool {
+        let (word, bit) = self.bit(i);
+        self.words[word] & bit != 0
+    }
+
+    /// Get the cell at `pos`, with everything off the grid unset
+    pub fn get_pos(&self, pos: Pos) -> bool {
+        (0..self.width as i64).contains(&pos.x)
+            && (0..self.height as i64).contains(&pos.y)
+            && self.get(self.to_index(pos.x as usize, pos.y as usize))
+    }
+
+    pub fn set(&mut self, i: usize, value: bool) {
+        let (word, bit) = self.bit(i);
+
+        if value {
+            self.words[word] |= bit;
+        } else {
+            self.words[word] &= !bit;
+        }
+    }
+
+    /// Set cell `i`, returning whether it was unset before
+    pub fn insert(&mut self, i: usize) -> bool {
+        let (word, bit) = self.bit(i);
+        let was_unset = self.words[word] & bit == 0;
+        self.words[word] |= bit;
+        was_unset
+    }
+
+    pub fn clear(&mut self) {
+        self.words.fill(0);
+    }
+
+    /// Number of set cells
+    pub fn count(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /// Indices of the set cells in order
+    pub fn iter_ones(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(move |(w, &word)| {
+            let first = (w / self.stride) * self.width + (w % self.stride) * 64;
+
+            // clear the lowest set bit until none are left
+            let nonzero = |w: u64| (w != 0).then_some(w);
+            std::iter::successors(nonzero(word), move |rest| {
+                nonzero(rest & (rest - 1))
+            })
+            .map(move |rest| first + rest.trailing_zeros() as usize)
+        })
+    }
+
+    pub fn to_col_row(&self, i: usize) -> (usize, usize) {
+        (i % self.width, i / self.width)
+    }
+
+    pub fn to_index(&self, col: usize, row: usize) -> usize {
+        col + row * self.width
+    }
+
+    pub fn step_from_index<D>(&self, i: usize, dir: D) -> Option<usize>
+    where
+        FullCompass: From<D>,
+    {
+        Topology::BOUNDED.step(self.width, self.height, i, dir.into())
+    }
+
+    pub fn neighbors(
+        &self,
+        i: usize,
+    ) -> impl Iterator<Item = (Compass, usize)> + '_ {
+        Compass::iter().filter_map(move |dir| {
+            self.step_from_index(i, dir).map(move |j| (dir, j))
+        })
+    }
+
+    /// Move every cell `dx` columns right and `dy` rows down. Cells moved
+    /// off the grid are dropped.
+    pub fn shifted(&self, dx: i64, dy: i64) -> Self {
+        let mut result = Self::new(self.width, self.height);
+        let stride = self.stride;
+        if stride == 0 {
+            return result;
+        }
+        let (words, bits) = ((dx.unsigned_abs() / 64) as usize, dx.abs() % 64);
+
+        for row in 0..self.height {
+            let Some(src_row) = row
+                .checked_add_signed(-dy as isize)
+                .filter(|r| *r < self.height)
+            else {
+                continue;
+            };
+
+            let src = &self.words[src_row * stride..(src_row + 1) * stride];
+            let get = |k: Option<usize>| k.and_then(|k| src.get(k)).copied();
+            let dst = &mut result.words[row * stride..(row + 1) * stride];
+
+            for (k, word) in dst.iter_mut().enumerate() {
+                *word = if dx >= 0 {
+                    let near = get(k.checked_sub(words)).unwrap_or(0);
+                    let far = get(k.checked_sub(words + 1)).unwrap_or(0);
+                    (near << bits)
+                        | far.checked_shr(64 - bits as u32).unwrap_or(0)
+                } else {
+                    let near = get(Some(k + words)).unwrap_or(0);
+                    let far = get(Some(k + words + 1)).unwrap_or(0);
+                    (near >> bits)
+                        | far.checked_shl(64 - bits as u32).unwrap_or(0)
+                };
+            }
+
+            dst[stride - 1] &= self.tail_mask();
+        }
+
+        result
+    }
+
+    fn neighbor_layers(
+        &self,
+        diagonal: bool,
+    ) -> impl Iterator<Item = BitGrid> + '_ {
+        let count = if diagonal { 8 } else { 4 };
+
+        Self::OFFSETS[..count]
+            .iter()
+            .map(|(dx, dy)| self.shifted(*dx, *dy))
+    }
+
+    /// Number of set neighbors of cell `i`, including diagonal ones if
+    /// `diagonal` is set
+    pub fn count_neighbors(&self, i: usize, diagonal: bool) -> usize {
+        let count = if diagonal { 8 } else { 4 };
+        let (col, row) = self.to_col_row(i);
+        let pos = Pos::new(col as i64, row as i64);
+
+        Self::OFFSETS[..count]
+            .iter()
+            .filter(|(dx, dy)| self.get_pos(pos + Vec2::new(*dx, *dy)))
+            .count()
+    }
+
+    /// Number of set neighbors of every cell
+    pub fn neighbor_counts(&self, diagonal: bool) -> Grid<u8> {
+        let mut counts =
+            Grid::new(vec![0; self.width * self.height], self.width);
+
+        for layer in self.neighbor_layers(diagonal) {
+            layer.iter_ones().for_each(|i| counts.data[i] += 1);
+        }
+
+        counts
+    }
+
+    /// Set every cell next to a set cell
+    pub fn dilate(&self, diagonal: bool) -> Self {
+        self.neighbor_layers(diagonal)
+            .fold(self.clone(), |acc, layer| &acc | &layer)
+    }
+
+    /// Keep only the set cells whose neighbors are all set. Cells off the
+    /// grid count as unset.
+    pub fn erode(&self, diagonal: bool) -> Self {
+        self.neighbor_layers(diagonal)
+            .fold(self.clone(), |acc, layer| &acc & &layer)
+    }
+
+    pub fn to_grid<T: Clone>(&self, on: T, off: T) -> Grid<T> {
+        let data = (0..self.width * self.height)
+            .map(|i| if self.get(i) { on.clone() } else { off.clone() })
+            .collect();
+
+        Grid::new(data, self.width)
+    }
+
+    fn zip_with<F>(&self, other: &Self, f: F) -> Self
+    where
+        F: Fn(u64, u64) -> u64,
+    {
+        debug_assert_eq!(
+            (self.width, self.height),
+            (other.width, other.height)
+        );
+
+        Self {
+            words: self
+                .words
+                .iter()
+                .zip(&other.words)
+                .map(|(a, b)| f(*a, *b))
+                .collect(),
+            ..self.clone()
+        }
+    }
+}
+
+impl BitAnd for &BitGrid {
+    type Output = BitGrid;
+    fn bitand(self, rhs: Self) -> BitGrid {
+        self.zip_with(rhs, |a, b| a & b)
+    }
+}
+
+impl BitOr for &BitGrid {
+    type Output = BitGrid;
+    fn bitor(self, rhs: Self) -> BitGrid {
+        self.zip_with(rhs, |a, b| a | b)
+    }
+}
+
+impl BitXor for &BitGrid {
+    type Output = BitGrid;
+    fn bitxor(self, rhs: Self) -> BitGrid {
+        self.zip_with(rhs, |a, b| a ^ b)
+    }
+}
+
+impl Not for &BitGrid {
+    type Output = BitGrid;
+    fn not(self) -> BitGrid {
+        let mut result = self.zip_with(self, |a, _| !a);
+        let mask = self.tail_mask();
+        if self.stride == 0 {
+            return result;
+        }
+
+        result
+            .words
+            .chunks_mut(self.stride)
+            .for_each(|row| row[self.stride - 1] &= mask);
+
+        result
+    }
+}
+
+impl std::fmt::Display for BitGrid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in 0..self.height {
+            let line = (0..self.width)
+                .map(|col| {
+                    if self.get(self.to_index(col, row)) {
+                        '#'
+                    } else {
+                        '.'
+                    }
+                })
+                .collect::<String>();
+            writeln!(f, "{line}")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compass_algebra() {
+        for d in Compass::iter() {
+            assert_eq!(d.rotate(4), d);
+            assert_eq!(d.rotate(-1), d.turn_left());
+            assert_eq!(Compass::from_index(d.index()), d);
+            assert_eq!(Compass::try_from(d.to_relative()), Ok(d));
+            assert_eq!(Compass::try_from(d.to_letter()), Ok(d));
+            assert_eq!(Compass::try_from(char::from(d.to_arrow())), Ok(d));
+
+            for turn in Turn::iter() {
+                assert_eq!(d.turn_to(d.turn(turn)), turn);
+            }
+        }
+
+        assert_eq!(Compass::N.turn_to(Compass::W), Turn::Left);
+        assert_eq!(Compass::E.turn_to(Compass::W).quarter_turns(), 2);
+        assert_eq!(Compass::try_from('x'), Err('x'));
+    }
+
+    #[test]
+    fn full_compass_turns() {
+        assert_eq!(FullCompass::N.turn_right(), FullCompass::NE);
+        assert_eq!(FullCompass::N.turn_left(), FullCompass::NW);
+        assert_eq!(FullCompass::SW.opposite(), FullCompass::NE);
+        assert_eq!(FullCompass::W.rotate(-10), FullCompass::S);
+        assert!(FullCompass::SE.is_diagonal());
+        assert_eq!(Compass::try_from(FullCompass::E), Ok(Compass::E));
+        assert_eq!(FullCompass::from_name("nw"), Some(FullCompass::NW));
+    }
+
+    #[test]
+    fn dir_map() {
+        let mut map = DirMap::from_fn(|d| d.to_letter());
+        map[Compass::S] = 's';
+
+        assert_eq!(map[Compass::E], 'E');
+        assert_eq!(map.values().collect::<String>(), "NEsW");
+        assert_eq!(map.iter().nth(3), Some((Compass::W, &'W')));
+    }
+
+    #[test]
+    fn vec2_arithmetic() {
+        let p = Pos::new(2, 3);
+        assert_eq!(p + Compass::N.into(), Pos::new(2, 2));
+        assert_eq!(p - Vec2::new(3, 3), Pos::new(-1, 0));
+        assert_eq!(-p * 2, Vec2::new(-4, -6));
+        assert_eq!(Vec2::from(Compass::E).rotate_right(), Compass::S.into());
+        assert_eq!(
+            FullCompass::from_vec(Vec2::new(-1, 1)),
+            Some(FullCompass::SW)
+        );
+        assert_eq!(Vec2::new(-3, 4).manhattan(), 7);
+    }
+
+    #[test]
+    fn grid_positions() {
+        let grid = Grid::new(vec![0, 1, 2, 3, 4, 5], 3);
+        assert_eq!(grid.get(Pos::new(1, 1)), Some(&4));
+        assert_eq!(grid.get(Pos::new(-1, 0)), None);
+        assert_eq!(grid.get(Pos::new(3, 0)), None);
+        assert!(!grid.contains(Pos::new(0, 2)));
+        assert_eq!(grid.wrap(Pos::new(-1, 5)), Pos::new(2, 1));
+        assert_eq!(grid.to_pos(5), Pos::new(2, 1));
+    }
+
+    #[test]
+    fn try_parse_grids() {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        struct Wall(bool);
+
+        impl TryFrom<char> for Wall {
+            type Error = ();
+            fn try_from(c: char) -> Result<Self, ()> {
+                match c {
+                    '#' => Ok(Wall(true)),
+                    '.' => Ok(Wall(false)),
+                    _ => Err(()),
+                }
+            }
+        }
+
+        let grid = Grid::<Wall>::try_parse_lines("#.\n.#\n").unwrap();
+        assert_eq!((grid.width, grid.height), (2, 2));
+        assert_eq!(grid.data[3], Wall(true));
+
+        assert_eq!(
+            Grid::<Wall>::try_parse_lines("#.\n.x"),
+            Err(GridParseError::Cell {
+                pos: Pos::new(1, 1),
+                c: 'x',
+                error: ()
+            })
+        );
+        assert_eq!(
+            Grid::<Wall>::try_parse_lines("#.\n.").unwrap_err(),
+            GridParseError::Width {
+                row: 1,
+                expected: 2,
+                found: 1
+            }
+        );
+        assert_eq!(
+            Grid::<Wall>::try_parse_many("#.\n..\n\n##\n#")
+                .unwrap_err()
+                .0,
+            1
+        );
+
+        let (grid, markers) =
+            Grid::<Wall>::parse_with_markers("S.\n#E", "SE", |_| Wall(false))
+                .unwrap();
+        assert_eq!(markers[&'S'], vec![0]);
+        assert_eq!(markers[&'E'], vec![3]);
+        assert_eq!(grid.data[3], Wall(false));
+    }
+
+    #[test]
+    fn parse_many_grids() {
+        let grids: Vec<Grid<char>> = Grid::parse_many("ab\ncd\n\nef\n");
+        assert_eq!(grids.len(), 2);
+        assert_eq!(grids[1].data, vec!['e', 'f']);
+    }
+
+    #[test]
+    fn torus_topology() {
+        let grid =
+            Grid::new((0..12).collect_vec(), 4).with_topology(Topology::TORUS);
+        assert_eq!(grid.step_from_index(0, Compass::W), Some(3));
+        assert_eq!(grid.step_from_index(0, FullCompass::NW), Some(11));
+        assert_eq!(grid.neighbors(5).count(), 4);
+        assert_eq!(grid.min_dist(0, 11), 2);
+        assert!(grid.are_neighbors(0, 8));
+        assert_eq!(grid.border().count(), 0);
+        assert!(!grid.is_border(0));
+        assert_eq!(
+            grid.translate(Pos::new(1, 1), Vec2::new(-7, 10)),
+            Some(Pos::new(2, 2))
+        );
+
+        let ray = grid.ray(1, FullCompass::E).map(|(i, _)| i).collect_vec();
+        assert_eq!(ray, vec![1, 2, 3, 0]);
+    }
+
+    #[test]
+    fn cylinder_topology() {
+        let grid =
+            Grid::new((0..12).collect_vec(), 4).with_topology(Topology {
+                wrap_x: true,
+                wrap_y: false,
+            });
+        assert_eq!(grid.step_from_index(4, Compass::W), Some(7));
+        assert_eq!(grid.step_from_index(0, Compass::N), None);
+        assert_eq!(grid.border().collect_vec(), vec![0, 1, 2, 3, 8, 9, 10, 11]);
+        assert!(grid.is_border(9));
+        assert!(!grid.is_border(4));
+        assert_eq!(grid.ray(4, FullCompass::S).count(), 2);
+        assert_eq!(
+            grid.translate(Pos::new(0, 0), Vec2::new(-1, 2)),
+            Some(Pos::new(3, 2))
+        );
+        assert_eq!(grid.translate(Pos::new(0, 0), Vec2::new(0, 3)), None);
+    }
+
+    #[test]
+    fn sparse_grid_bounds() {
+        let mut grid: SparseGrid<char> =
+            [(Pos::new(-2, 1), '#'), (Pos::new(3, -1), '#')]
+                .into_iter()
+                .collect();
+        assert_eq!(
+            grid.bounding_box(),
+            Some((Pos::new(-2, -1), Pos::new(3, 1)))
+        );
+
+        grid.insert(Pos::new(0, 0), 'o');
+        grid.remove(Pos::new(3, -1));
+        assert_eq!(
+            grid.bounding_box(),
+            Some((Pos::new(-2, 0), Pos::new(0, 1)))
+        );
+        assert_eq!(grid.render(' ', |c| *c), "  o\n#  ");
+
+        let ray = grid.ray(Pos::new(-2, 1), FullCompass::E).collect_vec();
+        assert_eq!(ray.len(), 3);
+        assert_eq!(ray[0], (Pos::new(-2, 1), Some(&'#')));
+    }
+
+    #[test]
+    fn sparse_grid_round_trip() {
+        let dense: Grid<char> = Grid::parse_lines("..#\n#..");
+        let sparse =
+            SparseGrid::from_grid_filtered(dense.clone(), |c| *c == '#');
+        assert_eq!(sparse.len(), 2);
+
+        let (back, origin) = sparse.to_grid('.').unwrap();
+        assert_eq!(back, dense);
+        assert_eq!(origin, Pos::ZERO);
+    }
+
+    #[test]
+    fn bit_grid_shifts() {
+        // wider than a word, so shifts have to carry between words
+        let grid: Grid<char> = Grid::parse_lines(&format!(
+            "{}\n{}",
+            "#".repeat(70),
+            ".".repeat(63) + "#" + &".".repeat(6)
+        ));
+        let bits = BitGrid::from_grid(&grid, |c| *c == '#');
+        assert_eq!(bits.count(), 71);
+        assert_eq!(bits.iter_ones().last(), Some(70 + 63));
+
+        let right = bits.shifted(1, 0);
+        assert_eq!(right.count(), 70);
+        assert!(right.get(70 + 64) && !right.get(0));
+
+        let left = bits.shifted(-65, 1);
+        assert_eq!(left.iter_ones().collect_vec(), (70..75).collect_vec());
+        assert_eq!(bits.to_grid('#', '.'), grid);
+        assert_eq!((!&bits).count(), 140 - 71);
+        assert_eq!((&bits ^ &right).count(), 3);
+    }
+
+    #[test]
+    fn bit_grid_zero_width() {
+        let bits = BitGrid::new(0, 3);
+        assert_eq!(bits.shifted(1, 1).count(), 0);
+        assert_eq!((!&bits).count(), 0);
+    }
+
+    #[test]
+    fn bit_grid_neighbors() {
+        let grid: Grid<char> = Grid::parse_lines("...\n.#.\n...");
+        let bits = BitGrid::from_grid(&grid, |c| *c == '#');
+
+        assert_eq!(bits.dilate(false).to_string(), ".#.\n###\n.#.\n");
+        assert_eq!(bits.dilate(true).count(), 9);
+        assert_eq!(
+            bits.dilate(true).erode(false),
+            bits.dilate(true).erode(true)
+        );
+        assert_eq!(bits.dilate(false).erode(false), bits);
+        assert_eq!(
+            bits.neighbor_counts(true).data,
+            [1, 1, 1, 1, 0, 1, 1, 1, 1]
+        );
+        assert_eq!(bits.count_neighbors(1, false), 1);
+        assert_eq!(bits.count_neighbors(0, false), 0);
+        assert_eq!(bits.neighbors(0).count(), 2);
+    }
+}
@@ -1,92 +1,180 @@
-use advent_of_code::template::commands::{all, download, read, scaffold, solve};
-use args::{parse, AppArguments};
+use std::{env, process};
+
+use advent_of_code::template::{
+    commands::{all, download, generate, read, scaffold, solve, test},
+    config::{self, Config, CONFIG_ENV},
+};
+use args::{AppArguments, Command};
+use clap::{CommandFactory, Parser};
 
 mod args {
-    use std::process;
+    use std::path::PathBuf;
+
+    use advent_of_code::{template::runner::RunOptions, Day};
+    use clap::{Parser, Subcommand};
+    use clap_complete::Shell;
 
-    use advent_of_code::Day;
+    /// Solve, benchmark and manage Advent of Code puzzles.
+    #[derive(Parser)]
+    #[command(name = "advent_of_code", version)]
+    pub struct AppArguments {
+        /// Read settings from this file instead of `aoc.toml`.
+        #[arg(long, global = true, value_name = "PATH")]
+        pub config: Option<PathBuf>,
+        /// Puzzle year. Overrides `year` in the config file.
+        #[arg(long, global = true)]
+        pub year: Option<u16>,
+        #[command(subcommand)]
+        pub command: Command,
+    }
 
-    pub enum AppArguments {
+    #[derive(Subcommand)]
+    pub enum Command {
+        /// Download the puzzle input and description via aoc-cli.
         Download {
+            /// Day of advent, 1 to 25.
             day: Day,
         },
+        /// Print the downloaded puzzle description, or fetch it via aoc-cli.
         Read {
+            /// Day of advent, 1 to 25.
             day: Day,
+            /// Only show this part.
+            #[arg(long, value_parser = clap::value_parser!(u8).range(1..=2))]
+            part: Option<u8>,
+            /// Wrap text at this many columns.
+            #[arg(long)]
+            width: Option<usize>,
+            /// Save the n-th code block of the description as the day's
+            /// example input.
+            #[arg(long, value_name = "BLOCK")]
+            save_example: Option<usize>,
+            /// Save the example as extra example `NN-<NUMBER>.txt`.
+            #[arg(long, value_name = "NUMBER", requires = "save_example")]
+            example_number: Option<u32>,
+            /// Replace an existing example file.
+            #[arg(long, requires = "save_example")]
+            force: bool,
         },
+        /// Create the solution module and empty data files for a day.
         Scaffold {
+            /// Day of advent, 1 to 25.
             day: Day,
+            /// Module template to use. Overrides `scaffold.template` in the
+            /// config file.
+            #[arg(long, value_name = "PATH")]
+            template: Option<PathBuf>,
         },
+        /// Run the solution for a single day.
         Solve {
+            /// Day of advent, 1 to 25.
             day: Day,
+            /// Run an optimized build.
+            #[arg(long)]
             release: bool,
-            time: bool,
-            submit: Option<u8>,
+            #[command(flatten)]
+            options: RunOptions,
         },
+        /// Run the solutions for all days.
         All {
+            /// Run optimized builds.
+            #[arg(long)]
             release: bool,
+            /// Benchmark each part. With `--release` this also updates the
+            /// README benchmark table.
+            #[arg(long)]
             time: bool,
         },
+        /// Run the example tests of the given days (all by default) and the
+        /// library tests, then print a pass/fail grid.
+        Test {
+            /// Days of advent, 1 to 25.
+            days: Vec<Day>,
+            /// Run the tests with an optimized build.
+            #[arg(long)]
+            release: bool,
+        },
+        /// Write synthetic stress test inputs for a day.
+        Generate {
+            /// Day of advent, 1 to 25.
+            day: Day,
+            /// Day-specific input size. May be given multiple times.
+            #[arg(long = "size", value_name = "N", required = true)]
+            sizes: Vec<usize>,
+            /// Seed for reproducible output.
+            #[arg(long)]
+            seed: Option<u64>,
+        },
+        /// Print a shell completion script to stdout.
+        Completions {
+            /// Shell to generate the script for.
+            shell: Shell,
+        },
     }
+}
 
-    pub fn parse() -> Result<AppArguments, Box<dyn std::error::Error>> {
-        let mut args = pico_args::Arguments::from_env();
-
-        let app_args = match args.subcommand()?.as_deref() {
-            Some("all") => AppArguments::All {
-                release: args.contains("--release"),
-                time: args.contains("--time"),
-            },
-            Some("download") => AppArguments::Download {
-                day: args.free_from_str()?,
-            },
-            Some("read") => AppArguments::Read {
-                day: args.free_from_str()?,
-            },
-            Some("scaffold") => AppArguments::Scaffold {
-                day: args.free_from_str()?,
-            },
-            Some("solve") => AppArguments::Solve {
-                day: args.free_from_str()?,
-                release: args.contains("--release"),
-                submit: args.opt_value_from_str("--submit")?,
-                time: args.contains("--time"),
-            },
-            Some(x) => {
-                eprintln!("Unknown command: {x}");
-                process::exit(1);
-            }
-            None => {
-                eprintln!("No command specified.");
-                process::exit(1);
-            }
-        };
+fn main() {
+    let args = AppArguments::parse();
 
-        let remaining = args.finish();
-        if !remaining.is_empty() {
-            eprintln!("Warning: unknown argument(s): {remaining:?}.");
+    let config = match Config::load(args.config.as_deref()) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            process::exit(1);
         }
+    };
 
-        Ok(app_args)
+    if let Some(year) = args.year {
+        // the env var takes precedence over the file, also in child processes.
+        env::set_var("AOC_YEAR", year.to_string());
     }
-}
 
-fn main() {
-    match parse() {
-        Err(err) => {
-            eprintln!("Error: {err}");
-            std::process::exit(1);
+    if let Some(path) = &args.config {
+        // solution binaries pick up the same config file.
+        env::set_var(CONFIG_ENV, path);
+    }
+
+    config::init(config);
+
+    match args.command {
+        Command::All { release, time } => all::handle(release, time),
+        Command::Download { day } => download::handle(day),
+        Command::Read {
+            day,
+            part,
+            width,
+            save_example,
+            example_number,
+            force,
+        } => {
+            let save = save_example.map(|block| read::SaveExample {
+                block,
+                number: example_number,
+                force,
+            });
+            read::handle(day, part.map(usize::from), width, save)
+        }
+        Command::Scaffold { day, template } => {
+            scaffold::handle(day, template.as_deref())
+        }
+        Command::Solve {
+            day,
+            release,
+            options,
+        } => solve::handle(day, release, &options),
+        Command::Test { days, release } => test::handle(&days, release),
+        Command::Generate { day, sizes, seed } => {
+            generate::handle(day, &sizes, seed)
+        }
+        Command::Completions { shell } => {
+            let mut cmd = AppArguments::command();
+            let name = cmd.get_name().to_string();
+            clap_complete::generate(
+                shell,
+                &mut cmd,
+                name,
+                &mut std::io::stdout(),
+            );
         }
-        Ok(args) => match args {
-            AppArguments::All { release, time } => all::handle(release, time),
-            AppArguments::Download { day } => download::handle(day),
-            AppArguments::Read { day } => read::handle(day),
-            AppArguments::Scaffold { day } => scaffold::handle(day),
-            AppArguments::Solve {
-                day,
-                release,
-                time,
-                submit,
-            } => solve::handle(day, release, time, submit),
-        },
     };
 }
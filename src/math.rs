@@ -0,0 +1,304 @@
+//! Number theory and exact linear algebra over integers. Functions that can
+//! overflow have a `checked_` variant returning `None` instead of panicking.
+
+use num_traits::{PrimInt, Signed};
+
+/// Greatest common divisor, never negative
+pub fn gcd<T: PrimInt>(mut a: T, mut b: T) -> T {
+    while b != T::zero() {
+        (a, b) = (b, a % b);
+    }
+
+    if a < T::zero() {
+        T::zero() - a
+    } else {
+        a
+    }
+}
+
+/// Least common multiple, never negative
+pub fn lcm<T: PrimInt>(a: T, b: T) -> T {
+    checked_lcm(a, b).expect("overflow in lcm")
+}
+
+pub fn checked_lcm<T: PrimInt>(a: T, b: T) -> Option<T> {
+    if a == T::zero() || b == T::zero() {
+        return Some(T::zero());
+    }
+
+    let l = (a / gcd(a, b)).checked_mul(&b)?;
+
+    if l < T::zero() {
+        T::zero().checked_sub(&l)
+    } else {
+        Some(l)
+    }
+}
+
+/// Least common multiple of all values, 1 if there are none
+pub fn lcm_all<T: PrimInt>(values: impl IntoIterator<Item = T>) -> T {
+    values.into_iter().fold(T::one(), lcm)
+}
+
+pub fn checked_lcm_all<T: PrimInt>(
+    values: impl IntoIterator<Item = T>,
+) -> Option<T> {
+    values
+        .into_iter()
+        .try_fold(T::one(), |acc, v| checked_lcm(acc, v))
+}
+
+/// `(g, x, y)` with `a * x + b * y = g` and `g` the gcd of `a` and `b`
+pub fn extended_gcd<T: PrimInt + Signed>(a: T, b: T) -> (T, T, T) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (T::one(), T::zero());
+    let (mut old_y, mut y) = (T::zero(), T::one());
+
+    while r != T::zero() {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+
+    if old_r < T::zero() {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// The `x` in `0..m` with `a * x = 1 (mod m)`, if `a` and `m` are coprime
+pub fn mod_inverse<T: PrimInt + Signed>(a: T, m: T) -> Option<T> {
+    let (g, x, _) = extended_gcd(a, m);
+    let m = m.abs();
+
+    (g == T::one()).then(|| (x % m + m) % m)
+}
+
+/// Merge `x = r1 (mod m1)` and `x = r2 (mod m2)`. The outer `None` is an
+/// overflow, the inner one means there is no solution.
+fn merge_congruences<T: PrimInt + Signed>(
+    (r1, m1): (T, T),
+    (r2, m2): (T, T),
+) -> Option<Option<(T, T)>> {
+    let (g, p, _) = extended_gcd(m1, m2);
+    let diff = r2.checked_sub(&r1)?;
+
+    if diff % g != T::zero() {
+        return Some(None);
+    }
+
+    let step = m2 / g;
+    let k = (diff / g % step).checked_mul(&(p % step))? % step;
+    let m = (m1 / g).checked_mul(&m2)?.abs();
+    let r = r1.checked_add(&m1.checked_mul(&k)?)? % m;
+
+    Some(Some((r.checked_add(&m)? % m, m)))
+}
+
+fn try_crt<T: PrimInt + Signed>(
+    congruences: impl IntoIterator<Item = (T, T)>,
+) -> Option<Option<(T, T)>> {
+    let mut acc = (T::zero(), T::one());
+
+    for (r, m) in congruences {
+        // `r % m` without panicking on a modulus of 0 or `MIN % -1`
+        let r = r - r.checked_div(&m)? * m;
+
+        match merge_congruences(acc, (r, m))? {
+            Some(merged) => acc = merged,
+            None => return Some(None),
+        }
+    }
+
+    Some(Some(acc))
+}
+
+/// Chinese remainder theorem. Solves `x = r (mod m)` for all `(r, m)` and
+/// returns `(x, lcm of the moduli)` with `x` in `0..lcm`. The moduli need
+/// not be coprime, `None` means the congruences contradict each other.
+pub fn crt<T: PrimInt + Signed>(
+    congruences: impl IntoIterator<Item = (T, T)>,
+) -> Option<(T, T)> {
+    let congruences = congruences.into_iter().inspect(|(_, m)| {
+        assert!(*m != T::zero(), "a modulus of 0 has no remainders");
+    });
+
+    try_crt(congruences).expect("overflow in crt")
+}
+
+/// [`crt`], also returning `None` on overflow and for a modulus of 0
+pub fn checked_crt<T: PrimInt + Signed>(
+    congruences: impl IntoIterator<Item = (T, T)>,
+) -> Option<(T, T)> {
+    try_crt(congruences).flatten()
+}
+
+fn try_solve2<T: PrimInt + Signed>(
+    [[a, b], [c, d]]: [[T; 2]; 2],
+    [x, y]: [T; 2],
+) -> Option<Option<[T; 2]>> {
+    let det = a.checked_mul(&d)?.checked_sub(&b.checked_mul(&c)?)?;
+    let num_0 = x.checked_mul(&d)?.checked_sub(&b.checked_mul(&y)?)?;
+    let num_1 = a.checked_mul(&y)?.checked_sub(&x.checked_mul(&c)?)?;
+
+    let divides = det != T::zero()
+        && num_0 % det == T::zero()
+        && num_1 % det == T::zero();
+
+    Some(divides.then(|| [num_0 / det, num_1 / det]))
+}
+
+/// The integer solution of the 2×2 system `matrix * v = rhs` by Cramer's
+/// rule, `None` if there is no unique one or it isn't integral
+pub fn solve2<T: PrimInt + Signed>(
+    matrix: [[T; 2]; 2],
+    rhs: [T; 2],
+) -> Option<[T; 2]> {
+    try_solve2(matrix, rhs).expect("overflow in solve2")
+}
+
+/// [`solve2`], also returning `None` on overflow
+pub fn checked_solve2<T: PrimInt + Signed>(
+    matrix: [[T; 2]; 2],
+    rhs: [T; 2],
+) -> Option<[T; 2]> {
+    try_solve2(matrix, rhs).flatten()
+}
+
+/// Fraction free Gauss-Jordan elimination. Every intermediate value is a
+/// minor of the augmented matrix, so the divisions are exact.
+fn try_solve_linear<T: PrimInt + Signed>(
+    matrix: &[Vec<T>],
+    rhs: &[T],
+) -> Option<Option<(Vec<T>, T)>> {
+    let n = rhs.len();
+    assert!(
+        matrix.len() == n && matrix.iter().all(|row| row.len() == n),
+        "the matrix must be square and match the right hand side"
+    );
+
+    let mut m = matrix
+        .iter()
+        .zip(rhs)
+        .map(|(row, r)| row.iter().chain([r]).copied().collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    let mut prev = T::one();
+
+    for k in 0..n {
+        let Some(pivot) = (k..n).find(|&r| m[r][k] != T::zero()) else {
+            return Some(None);
+        };
+        m.swap(k, pivot);
+
+        for i in (0..n).filter(|&i| i != k) {
+            for j in (0..=n).filter(|&j| j != k) {
+                let keep = m[k][k].checked_mul(&m[i][j])?;
+                let cancel = m[i][k].checked_mul(&m[k][j])?;
+                m[i][j] = keep.checked_sub(&cancel)? / prev;
+            }
+            m[i][k] = T::zero();
+        }
+
+        prev = m[k][k];
+    }
+
+    // every diagonal entry is now the determinant, up to sign
+    let sign = prev.signum();
+    let numerators = m.iter().map(|row| row[n] * sign).collect();
+
+    Some(Some((numerators, prev.abs())))
+}
+
+/// The exact solution of the N×N system `matrix * v = rhs` as numerators
+/// over a shared positive denominator, `None` if the matrix is singular
+pub fn solve_linear<T: PrimInt + Signed>(
+    matrix: &[Vec<T>],
+    rhs: &[T],
+) -> Option<(Vec<T>, T)> {
+    try_solve_linear(matrix, rhs).expect("overflow in solve_linear")
+}
+
+/// [`solve_linear`], also returning `None` on overflow
+pub fn checked_solve_linear<T: PrimInt + Signed>(
+    matrix: &[Vec<T>],
+    rhs: &[T],
+) -> Option<(Vec<T>, T)> {
+    try_solve_linear(matrix, rhs).flatten()
+}
+
+/// The integer solution of `matrix * v = rhs`, `None` if the matrix is
+/// singular or the solution isn't integral
+pub fn solve_linear_integer<T: PrimInt + Signed>(
+    matrix: &[Vec<T>],
+    rhs: &[T],
+) -> Option<Vec<T>> {
+    let (numerators, denominator) = solve_linear(matrix, rhs)?;
+
+    numerators
+        .into_iter()
+        .map(|n| (n % denominator == T::zero()).then(|| n / denominator))
+        .collect()
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn number_theory() {
+        assert_eq!(gcd(12, -18), 6);
+        assert_eq!(gcd(0u32, 7), 7);
+        assert_eq!(lcm(4, 6), 12);
+        assert_eq!(lcm_all([101u64, 103, 4, 6]), 124_836);
+        assert_eq!(checked_lcm_all([u8::MAX, 2]), None);
+
+        let (g, x, y) = extended_gcd(240, 46);
+        assert_eq!(g, 2);
+        assert_eq!(240 * x + 46 * y, 2);
+
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(-3, 11), Some(7));
+        assert_eq!(mod_inverse(6, 9), None);
+
+        assert_eq!(crt([(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt([(1, 4), (3, 6)]), Some((9, 12)));
+        assert_eq!(crt([(1, 4), (2, 6)]), None);
+        assert_eq!(crt::<i64>([]), Some((0, 1)));
+        assert_eq!(checked_crt([(1i8, 100), (2, 99)]), None);
+        assert_eq!(checked_crt([(99i8, 100), (-49, 50)]), None);
+        assert_eq!(checked_crt([(i8::MIN, -1)]), None);
+        assert_eq!(checked_crt([(1, 3), (2, 0)]), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "a modulus of 0")]
+    fn crt_modulus_zero() {
+        crt([(1, 3), (2, 0)]);
+    }
+
+    #[test]
+    fn linear_systems() {
+        // the first claw machine from 2024 day 13
+        assert_eq!(solve2([[94, 22], [34, 67]], [8400, 5400]), Some([80, 40]));
+        assert_eq!(solve2([[26, 67], [66, 21]], [12748, 12176]), None);
+        assert_eq!(solve2([[1, 2], [2, 4]], [3, 6]), None);
+        assert_eq!(checked_solve2([[i8::MAX, 2], [2, i8::MAX]], [1, 1]), None);
+
+        let matrix = vec![vec![0, 2, 1], vec![1, -2, -3], vec![-1, 1, 2]];
+        assert_eq!(
+            solve_linear_integer(&matrix, &[-8, 0, 3]),
+            Some(vec![-4, -5, 2])
+        );
+        assert_eq!(
+            solve_linear(&[vec![2, 1], vec![1, 3]], &[1, 1]),
+            Some((vec![2, 1], 5))
+        );
+        assert_eq!(solve_linear(&[vec![1, 2], vec![2, 4]], &[1, 2]), None);
+        assert_eq!(
+            solve_linear_integer(&[vec![2, 1], vec![1, 3]], &[1, 1]),
+            None
+        );
+    }
+}
@@ -0,0 +1,232 @@
+//! Memoization for recursive solutions without recursion. A problem is
+//! described by the subproblems it depends on and how to combine their
+//! answers, and [`Memo::solve`] works through them with an explicit stack.
+//!
+//! With `--profile` the runner prints the cache statistics of every memo
+//! dropped during a part.
+
+use std::{
+    collections::HashMap,
+    fmt,
+    hash::Hash,
+    sync::atomic::{AtomicBool, AtomicU64, Ordering},
+};
+
+static PROFILING: AtomicBool = AtomicBool::new(false);
+static HITS: AtomicU64 = AtomicU64::new(0);
+static MISSES: AtomicU64 = AtomicU64::new(0);
+static ENTRIES: AtomicU64 = AtomicU64::new(0);
+
+/// Collect the statistics of dropped memos for [`take_stats`]
+pub fn set_profiling(enabled: bool) {
+    PROFILING.store(enabled, Ordering::Relaxed);
+}
+
+/// The summed statistics of all memos dropped since the last call, if any
+/// were dropped while profiling
+pub fn take_stats() -> Option<Stats> {
+    let stats = Stats {
+        hits: HITS.swap(0, Ordering::Relaxed),
+        misses: MISSES.swap(0, Ordering::Relaxed),
+        entries: ENTRIES.swap(0, Ordering::Relaxed),
+    };
+
+    (stats != Stats::default()).then_some(stats)
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    /// Lookups answered from the cache
+    pub hits: u64,
+    /// Lookups that had to be computed
+    pub misses: u64,
+    /// Answers in the cache
+    pub entries: u64,
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self {
+            hits,
+            misses,
+            entries,
+        } = self;
+        let rate = *hits as f64 / (hits + misses).max(1) as f64 * 100.;
+
+        write!(
+            f,
+            "{hits} hits, {misses} misses ({rate:.1}% hit rate), {entries} \
+             entries"
+        )
+    }
+}
+
+/// A cache of answers to subproblems
+pub struct Memo<K, V> {
+    cache: HashMap<K, V>,
+    hits: u64,
+    misses: u64,
+}
+
+impl<K, V> Default for Memo<K, V> {
+    fn default() -> Self {
+        Self {
+            cache: HashMap::new(),
+            hits: 0,
+            misses: 0,
+        }
+    }
+}
+
+impl<K, V> Memo<K, V>
+where
+    K: Clone + Eq + Hash,
+    V: Clone,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, key: &K) -> Option<&'_ V> {
+        self.cache.get(key)
+    }
+
+    /// Look up `key`, computing it with `f` if it isn't cached yet
+    pub fn get_or_insert_with<F>(&mut self, key: K, f: F) -> V
+    where
+        F: FnOnce(&K) -> V,
+    {
+        if let Some(v) = self.cache.get(&key) {
+            self.hits += 1;
+            return v.clone();
+        }
+
+        self.misses += 1;
+        let v = f(&key);
+        self.cache.insert(key, v.clone());
+        v
+    }
+
+    /// Solve `key` and everything it depends on. `deps` lists the
+    /// subproblems of a key, none for base cases, and `combine` computes the
+    /// answer of a key from the answers of its subproblems in the same
+    /// order. Subproblems must not depend on the key itself.
+    pub fn solve<D, I, C>(&mut self, key: K, mut deps: D, mut combine: C) -> V
+    where
+        D: FnMut(&K) -> I,
+        I: IntoIterator<Item = K>,
+        C: FnMut(&K, &[V]) -> V,
+    {
+        // a key is pushed twice: once to queue its subproblems and once more
+        // below them, with the subproblems listed, to combine their answers
+        let mut stack: Vec<(K, Option<Vec<K>>)> = vec![(key.clone(), None)];
+
+        while let Some((curr, subproblems)) = stack.pop() {
+            match subproblems {
+                Some(subproblems) => {
+                    let answers = subproblems
+                        .iter()
+                        .map(|k| self.cache[k].clone())
+                        .collect::<Vec<_>>();
+                    let answer = combine(&curr, &answers);
+                    self.cache.insert(curr, answer);
+                }
+                None if self.cache.contains_key(&curr) => self.hits += 1,
+                None => {
+                    self.misses += 1;
+                    let subproblems =
+                        deps(&curr).into_iter().collect::<Vec<_>>();
+                    let pending = subproblems
+                        .iter()
+                        .filter(|k| !self.cache.contains_key(k))
+                        .map(|k| (k.clone(), None))
+                        .collect::<Vec<_>>();
+                    self.hits += (subproblems.len() - pending.len()) as u64;
+
+                    stack.push((curr, Some(subproblems)));
+                    stack.extend(pending);
+                }
+            }
+        }
+
+        self.cache[&key].clone()
+    }
+
+    pub fn stats(&self) -> Stats {
+        Stats {
+            hits: self.hits,
+            misses: self.misses,
+            entries: self.cache.len() as u64,
+        }
+    }
+}
+
+impl<K, V> Drop for Memo<K, V> {
+    fn drop(&mut self) {
+        if PROFILING.load(Ordering::Relaxed) {
+            HITS.fetch_add(self.hits, Ordering::Relaxed);
+            MISSES.fetch_add(self.misses, Ordering::Relaxed);
+            ENTRIES.fetch_add(self.cache.len() as u64, Ordering::Relaxed);
+        }
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::*;
+
+    fn fib(memo: &mut Memo<u64, u64>, n: u64) -> u64 {
+        memo.solve(
+            n,
+            |n| if *n < 2 { vec![] } else { vec![n - 1, n - 2] },
+            |n, prev| {
+                if *n < 2 {
+                    *n
+                } else {
+                    prev[0].wrapping_add(prev[1])
+                }
+            },
+        )
+    }
+
+    #[test]
+    fn deep_recursion() {
+        let mut memo = Memo::new();
+
+        // deep enough to overflow the stack if solved recursively
+        let n = 200_000;
+        fib(&mut memo, n);
+        assert_eq!(memo.stats().entries, n + 1);
+
+        let mut memo = Memo::new();
+        assert_eq!(fib(&mut memo, 90), 2880067194370816120);
+        assert_eq!(memo.get(&10), Some(&55));
+
+        let before = memo.stats();
+        assert_eq!(fib(&mut memo, 50), 12586269025);
+        assert_eq!(memo.stats().hits, before.hits + 1);
+    }
+
+    #[test]
+    fn simple_lookups() {
+        let mut memo = Memo::new();
+        let mut calls = 0;
+
+        for n in [3, 4, 3, 3] {
+            memo.get_or_insert_with(n, |n| {
+                calls += 1;
+                n * n
+            });
+        }
+
+        assert_eq!(calls, 2);
+        assert_eq!(
+            memo.stats(),
+            Stats {
+                hits: 2,
+                misses: 2,
+                entries: 2
+            }
+        );
+    }
+}
@@ -0,0 +1,417 @@
+//! Parsers for the shapes that keep showing up in puzzle inputs, built on
+//! `nom`, and [`parse_all`] to run one over a whole input. Structs can also
+//! derive a parser from a format string with [`AocParse`].
+
+use std::{fmt, str::FromStr};
+
+use nom::{
+    branch::alt,
+    bytes::complete::{tag, take_until, take_while1},
+    character::complete::{
+        alphanumeric1, char, i64, line_ending, multispace0, satisfy, space0,
+        u64,
+    },
+    combinator::{all_consuming, map, map_parser, opt, recognize, rest},
+    error::{ErrorKind, ParseError},
+    multi::{many1, separated_list1},
+    sequence::{delimited, pair, preceded, separated_pair, terminated, tuple},
+    IResult, Offset, Parser,
+};
+
+use crate::Pos;
+
+pub use aoc_derive::AocParse;
+
+pub type ParseResult<'a, O> = IResult<&'a str, O>;
+
+/// Separator between list items: any mix of commas and whitespace
+fn list_sep<'a, E: ParseError<&'a str>>(
+    input: &'a str,
+) -> IResult<&'a str, &'a str, E> {
+    take_while1(|c: char| c == ',' || c.is_whitespace())(input)
+}
+
+/// Unsigned numbers separated by commas or whitespace, e.g. `1, 2 3`
+pub fn unsigned_list<'a, E: ParseError<&'a str>>(
+) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<u64>, E> {
+    delimited(multispace0, separated_list1(list_sep, u64), multispace0)
+}
+
+/// Signed numbers separated by commas or whitespace, e.g. `-1,2,-3`
+pub fn signed_list<'a, E: ParseError<&'a str>>(
+) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<i64>, E> {
+    delimited(multispace0, separated_list1(list_sep, i64), multispace0)
+}
+
+/// A signed `x,y` pair, allowing spaces after the comma
+pub fn coord<'a, E: ParseError<&'a str>>(
+) -> impl FnMut(&'a str) -> IResult<&'a str, Pos, E> {
+    map(
+        separated_pair(i64, pair(char(','), space0), i64),
+        |(x, y)| Pos::new(x, y),
+    )
+}
+
+/// A `key: value` pair on one line, e.g. `x00: 1`
+pub fn key_value<'a, K, V, FK, FV, E>(
+    key: FK,
+    value: FV,
+) -> impl FnMut(&'a str) -> IResult<&'a str, (K, V), E>
+where
+    FK: Parser<&'a str, K, E>,
+    FV: Parser<&'a str, V, E>,
+    E: ParseError<&'a str>,
+{
+    separated_pair(key, tuple((space0, char(':'), space0)), value)
+}
+
+/// A value after a fixed label, e.g. `labeled("Register A", i64)` for
+/// `Register A: 729`
+pub fn labeled<'a, O, F, E>(
+    label: &'static str,
+    value: F,
+) -> impl FnMut(&'a str) -> IResult<&'a str, O, E>
+where
+    F: Parser<&'a str, O, E>,
+    E: ParseError<&'a str>,
+{
+    preceded(tuple((space0, tag(label), char(':'), space0)), value)
+}
+
+/// Sections of the input separated by blank lines, each of which `inner`
+/// has to consume completely
+pub fn sections<'a, O, F, E>(
+    inner: F,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>, E>
+where
+    F: Parser<&'a str, O, E>,
+    E: ParseError<&'a str>,
+{
+    let section = map(alt((take_until("\n\n"), rest)), |s: &'a str| {
+        s.trim_end_matches('\n')
+    });
+    let blank_lines = recognize(pair(line_ending, many1(line_ending)));
+
+    separated_list1(blank_lines, map_parser(section, all_consuming(inner)))
+}
+
+/// All integers in `text`, ignoring everything around them. A `-` right
+/// before the digits makes the number negative if `T` is signed.
+pub fn integers<T: FromStr>(text: &str) -> impl Iterator<Item = T> + '_ {
+    let bytes = text.as_bytes();
+    let mut i = 0;
+
+    std::iter::from_fn(move || {
+        while i < bytes.len() {
+            let start = i;
+
+            if !bytes[i].is_ascii_digit() {
+                i += 1;
+                continue;
+            }
+
+            while i < bytes.len() && bytes[i].is_ascii_digit() {
+                i += 1;
+            }
+
+            let digits = &text[start..i];
+            let signed = start
+                .checked_sub(1)
+                .filter(|s| bytes[*s] == b'-')
+                .map(|s| &text[s..i]);
+
+            if let Some(n) = signed
+                .and_then(|s| s.parse().ok())
+                .or_else(|| digits.parse().ok())
+            {
+                return Some(n);
+            }
+        }
+
+        None
+    })
+}
+
+/// Where and why [`parse_all`] failed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error {
+    /// 1-based line of the failure
+    pub line: usize,
+    /// 1-based column of the failure
+    pub column: usize,
+    /// The failing parser, or `None` if the parser succeeded but left input
+    /// over
+    pub kind: Option<ErrorKind>,
+    /// The rest of the line where parsing stopped
+    pub leftover: String,
+}
+
+impl Error {
+    /// Locate `remaining`, which has to be a slice of `input`
+    fn at(input: &str, remaining: &str, kind: Option<ErrorKind>) -> Self {
+        let consumed = &input[..input.offset(remaining)];
+        let line_start = consumed.rfind('\n').map_or(0, |i| i + 1);
+
+        Self {
+            line: consumed.matches('\n').count() + 1,
+            column: consumed[line_start..].chars().count() + 1,
+            kind,
+            leftover: input[consumed.len()..]
+                .lines()
+                .next()
+                .unwrap_or("")
+                .to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self { line, column, .. } = self;
+
+        match self.kind {
+            Some(kind) => write!(f, "{kind:?} failed at {line}:{column}")?,
+            None => write!(f, "unparsed input at {line}:{column}")?,
+        }
+
+        write!(f, ": {:?}", self.leftover)
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Run `parser` over the whole of `input`. Trailing whitespace is allowed,
+/// anything else left over is an error.
+pub fn parse_all<'a, O, F>(mut parser: F, input: &'a str) -> Result<O, Error>
+where
+    F: Parser<&'a str, O, nom::error::Error<&'a str>>,
+{
+    match parser.parse(input) {
+        Ok((remaining, _)) if !remaining.trim_end().is_empty() => {
+            Err(Error::at(input, remaining, None))
+        }
+        Ok((_, output)) => Ok(output),
+        Err(nom::Err::Error(e) | nom::Err::Failure(e)) => {
+            Err(Error::at(input, e.input, Some(e.code)))
+        }
+        Err(nom::Err::Incomplete(_)) => Err(Error::at(
+            input,
+            &input[input.len()..],
+            Some(ErrorKind::Complete),
+        )),
+    }
+}
+
+/// A value with a parser for a single occurrence in the input. Derive it for
+/// a struct with a format string in which each `{}` is the next field:
+///
+/// ```ignore
+/// #[derive(AocParse)]
+/// #[aoc(fmt = "p={} v={}")]
+/// struct Robot {
+///     p: Pos,
+///     v: Pos,
+/// }
+/// ```
+///
+/// Each word of the format and each field is wrapped in [`ws`](crate::ws),
+/// so whitespace around them is optional, line breaks included.
+pub trait AocParse: Sized {
+    fn parse(input: &str) -> ParseResult<'_, Self>;
+
+    /// Parse the whole input as a single value
+    fn from_input(input: &str) -> Result<Self, Error> {
+        parse_all(Self::parse, input)
+    }
+
+    /// Parse one value per line
+    fn parse_lines(input: &str) -> Result<Vec<Self>, Error> {
+        parse_all(many1(terminated(Self::parse, opt(line_ending))), input)
+    }
+
+    /// Parse one value per section of blank line separated input
+    fn parse_sections(input: &str) -> Result<Vec<Self>, Error> {
+        parse_all(sections(Self::parse), input)
+    }
+}
+
+macro_rules! number_impls {
+    ($($t:ident),*) => {
+        $(impl AocParse for $t {
+            fn parse(input: &str) -> ParseResult<'_, Self> {
+                nom::character::complete::$t(input)
+            }
+        })*
+    };
+}
+
+number_impls!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128);
+
+impl AocParse for usize {
+    fn parse(input: &str) -> ParseResult<'_, Self> {
+        map(u64, |n| n as usize)(input)
+    }
+}
+
+impl AocParse for isize {
+    fn parse(input: &str) -> ParseResult<'_, Self> {
+        map(i64, |n| n as isize)(input)
+    }
+}
+
+/// Any character but whitespace
+impl AocParse for char {
+    fn parse(input: &str) -> ParseResult<'_, Self> {
+        satisfy(|c| !c.is_whitespace())(input)
+    }
+}
+
+/// A word of letters and digits
+impl AocParse for String {
+    fn parse(input: &str) -> ParseResult<'_, Self> {
+        map(alphanumeric1, str::to_string)(input)
+    }
+}
+
+/// An `x,y` pair, see [`coord`]
+impl AocParse for Pos {
+    fn parse(input: &str) -> ParseResult<'_, Self> {
+        coord()(input)
+    }
+}
+
+/// Values on one line separated by commas or spaces
+impl<T: AocParse> AocParse for Vec<T> {
+    fn parse(input: &str) -> ParseResult<'_, Self> {
+        let sep = take_while1(|c| c == ',' || c == ' ' || c == '\t');
+        separated_list1(sep, T::parse)(input)
+    }
+}
+
+/// Steps used by the code `#[derive(AocParse)]` generates
+#[doc(hidden)]
+pub mod __private {
+    use super::*;
+
+    use crate::ws;
+
+    pub fn literal<'a>(
+        text: &'static str,
+        input: &'a str,
+    ) -> ParseResult<'a, &'a str> {
+        ws(tag(text)).parse(input)
+    }
+
+    pub fn field<T: AocParse>(input: &str) -> ParseResult<'_, T> {
+        ws(T::parse).parse(input)
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::*;
+    use nom::character::complete::alpha1;
+
+    use crate::ws;
+
+    #[test]
+    fn number_lists() {
+        assert_eq!(
+            parse_all(unsigned_list(), "1 2\n3,4, 5\n"),
+            Ok(vec![1, 2, 3, 4, 5])
+        );
+        assert_eq!(parse_all(signed_list(), "-1,2,-3"), Ok(vec![-1, 2, -3]));
+
+        let err = parse_all(unsigned_list(), "1 2\n3 x 4").unwrap_err();
+        assert_eq!((err.line, err.column, err.kind), (2, 3, None));
+        assert_eq!(err.leftover, "x 4");
+        assert_eq!(err.to_string(), "unparsed input at 2:3: \"x 4\"");
+    }
+
+    #[test]
+    fn fields_and_coords() {
+        let robot =
+            preceded(tag("p="), separated_pair(coord(), tag(" v="), coord()));
+        assert_eq!(
+            parse_all(robot, "p=0,4 v=3,-3"),
+            Ok((Pos::new(0, 4), Pos::new(3, -3)))
+        );
+
+        assert_eq!(
+            parse_all(labeled("Register A", i64), "Register A: 729"),
+            Ok(729)
+        );
+        assert_eq!(
+            parse_all(many1(ws(key_value(alpha1, u64))), "x: 1\ny : 2"),
+            Ok(vec![("x", 1), ("y", 2)])
+        );
+
+        let err = parse_all(labeled("A", u64), "B: 1").unwrap_err();
+        assert_eq!(err.kind, Some(ErrorKind::Tag));
+    }
+
+    #[test]
+    fn blank_line_sections() {
+        let input = "1 2\n3\n\n4\n\n\n5 6\n";
+        assert_eq!(
+            parse_all(sections(unsigned_list()), input),
+            Ok(vec![vec![1, 2, 3], vec![4], vec![5, 6]])
+        );
+    }
+
+    #[derive(Debug, PartialEq, AocParse)]
+    #[aoc(fmt = "p={} v={}")]
+    struct Robot {
+        p: Pos,
+        v: Pos,
+    }
+
+    #[derive(Debug, PartialEq, AocParse)]
+    #[aoc(fmt = "{}: {}\n  moves {}")]
+    struct Entry(String, Vec<u8>, char);
+
+    #[test]
+    fn derived_parsers() {
+        let robots = Robot::parse_lines("p=0,4 v=3,-3\np=6,3 v=-1,-3\n");
+        assert_eq!(
+            robots.unwrap()[1],
+            Robot {
+                p: Pos::new(6, 3),
+                v: Pos::new(-1, -3)
+            }
+        );
+
+        let entries =
+            Entry::parse_sections("a1: 1, 2 3\nmoves >\n\nb:4\n moves <");
+        assert_eq!(
+            entries,
+            Ok(vec![
+                Entry("a1".to_string(), vec![1, 2, 3], '>'),
+                Entry("b".to_string(), vec![4], '<'),
+            ])
+        );
+
+        // whitespace is interchangeable, line breaks included
+        assert_eq!(
+            Robot::from_input(" p=0,4\nv=3,-3\n"),
+            Ok(Robot {
+                p: Pos::new(0, 4),
+                v: Pos::new(3, -3)
+            })
+        );
+        assert_eq!(
+            Entry::from_input("c: 5 moves ^"),
+            Ok(Entry("c".to_string(), vec![5], '^'))
+        );
+
+        let err = Robot::from_input("p=0,4\nw=3,-3").unwrap_err();
+        assert_eq!((err.line, err.kind), (2, Some(ErrorKind::Tag)));
+    }
+
+    #[test]
+    fn integers_in_text() {
+        let text = "Button A: X+94, Y-34\nrange 3-5";
+        assert_eq!(integers::<i64>(text).collect::<Vec<_>>(), [94, -34, 3, -5]);
+        assert_eq!(integers::<u32>(text).collect::<Vec<_>>(), [94, 34, 3, 5]);
+    }
+}
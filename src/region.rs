@@ -0,0 +1,348 @@
+//! Connected regions of a [`Grid`] and their geometry: area, perimeter,
+//! number of sides, bounding box and the outline as polygons.
+
+use std::collections::HashMap;
+
+use strum::IntoEnumIterator;
+
+use crate::{lattice::Lattice, Compass, FullCompass, Grid, Pos, Vec2};
+
+/// A connected group of cells
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Region {
+    /// Indices of the cells in the region
+    pub cells: Vec<usize>,
+    /// Number of cell sides facing a cell outside the region
+    pub perimeter: usize,
+    /// Number of straight sides of the outline, holes included. This is the
+    /// same as the number of corners.
+    pub sides: usize,
+    /// Smallest and largest cell positions (both inclusive)
+    pub bounds: (Pos, Pos),
+}
+
+impl Region {
+    pub fn area(&self) -> usize {
+        self.cells.len()
+    }
+}
+
+/// The connected regions of a grid together with the region label of every
+/// cell
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Regions {
+    /// Index into `regions` for each cell, or `None` for excluded cells
+    pub labels: Grid<Option<usize>>,
+    pub regions: Vec<Region>,
+}
+
+impl Regions {
+    fn inside<D>(&self, label: usize, i: usize, dir: D) -> bool
+    where
+        FullCompass: From<D>,
+    {
+        self.labels
+            .step_from_index(i, dir)
+            .is_some_and(|n| self.labels.data[n] == Some(label))
+    }
+
+    /// Trace the outline of a region as closed polygons with one vertex per
+    /// corner. Vertices are cell corners, where `(x, y)` is the top left
+    /// corner of the cell at `(x, y)`. Outer outlines run clockwise and holes
+    /// counter-clockwise, so the region is always on the right hand side. A
+    /// hole touching the outside at a corner is part of the outer outline.
+    ///
+    /// The outline is traced as if the grid didn't wrap, so on a wrapping
+    /// grid a region crossing the edge is cut into separate polygons there.
+    pub fn boundary(&self, label: usize) -> Vec<Vec<Pos>> {
+        let mut edges: HashMap<Pos, Vec<Compass>> = HashMap::new();
+
+        for &i in &self.regions[label].cells {
+            let p = self.labels.to_pos(i);
+            let inside = |d: Compass| {
+                self.labels.get(p + d.into()) == Some(&Some(label))
+            };
+
+            for side in Compass::iter().filter(|d| !inside(*d)) {
+                let (from, dir) = match side {
+                    Compass::N => (p, Compass::E),
+                    Compass::E => (p + Vec2::new(1, 0), Compass::S),
+                    Compass::S => (p + Vec2::new(1, 1), Compass::W),
+                    Compass::W => (p + Vec2::new(0, 1), Compass::N),
+                };
+                edges.entry(from).or_default().push(dir);
+            }
+        }
+
+        let mut polygons = vec![];
+
+        // start at the top left vertex, which is always a corner
+        while let Some(start) = edges.keys().min_by_key(|p| (p.y, p.x)).copied()
+        {
+            let mut dir = take_edge(
+                &mut edges,
+                start,
+                [Compass::E, Compass::S, Compass::N],
+            );
+            let mut polygon = vec![start];
+            let mut curr = start + dir.into();
+
+            // turning right first hugs the current cell, so cells that only
+            // touch at a corner are outlined separately
+            while curr != start {
+                let prev = dir;
+                dir = take_edge(
+                    &mut edges,
+                    curr,
+                    [dir.turn_right(), dir, dir.turn_left()],
+                );
+
+                if dir != prev {
+                    polygon.push(curr);
+                }
+
+                curr += dir.into();
+            }
+
+            polygons.push(polygon);
+        }
+
+        polygons
+    }
+
+    fn region(&self, label: usize, cells: Vec<usize>) -> Region {
+        let perimeter = cells
+            .iter()
+            .map(|&i| {
+                Compass::iter()
+                    .filter(|d| !self.inside(label, i, *d))
+                    .count()
+            })
+            .sum();
+
+        // every corner of the outline is either convex (both sides outside)
+        // or concave (both sides inside, the diagonal outside)
+        let sides = cells
+            .iter()
+            .map(|&i| {
+                Compass::iter()
+                    .filter(|d| {
+                        let r = d.turn_right();
+                        let diagonal =
+                            FullCompass::from_vec(Vec2::from(*d) + r.into())
+                                .unwrap();
+
+                        match (
+                            self.inside(label, i, *d),
+                            self.inside(label, i, r),
+                        ) {
+                            (false, false) => true,
+                            (true, true) => !self.inside(label, i, diagonal),
+                            _ => false,
+                        }
+                    })
+                    .count()
+            })
+            .sum();
+
+        let positions = cells.iter().map(|i| self.labels.to_pos(*i));
+        let min = positions
+            .clone()
+            .reduce(|a, b| Pos::new(a.x.min(b.x), a.y.min(b.y)))
+            .unwrap();
+        let max = positions
+            .reduce(|a, b| Pos::new(a.x.max(b.x), a.y.max(b.y)))
+            .unwrap();
+
+        Region {
+            cells,
+            perimeter,
+            sides,
+            bounds: (min, max),
+        }
+    }
+}
+
+/// Remove and return the first of the `preferred` edges leaving `from`
+fn take_edge(
+    edges: &mut HashMap<Pos, Vec<Compass>>,
+    from: Pos,
+    preferred: [Compass; 3],
+) -> Compass {
+    let dirs = edges.get_mut(&from).expect("outline is closed");
+    let k = preferred
+        .iter()
+        .find_map(|d| dirs.iter().position(|e| e == d))
+        .expect("outline is closed");
+    let dir = dirs.swap_remove(k);
+
+    if dirs.is_empty() {
+        edges.remove(&from);
+    }
+
+    dir
+}
+
+impl<T> Grid<T> {
+    /// All cells reachable from `start` by stepping to neighbors for which
+    /// `connected(from, to)` holds, in the order they were reached
+    pub fn flood_fill<F>(&self, start: usize, connected: F) -> Vec<usize>
+    where
+        F: FnMut(&T, &T) -> bool,
+    {
+        Lattice::flood_fill(self, start, connected)
+    }
+
+    /// Split the whole grid into regions, where neighboring cells belong to
+    /// the same region if `same` holds for them, e.g. `|a, b| a == b`
+    pub fn regions_by<F>(&self, same: F) -> Regions
+    where
+        F: FnMut(&T, &T) -> bool,
+    {
+        self.label_regions(|_| true, same)
+    }
+
+    /// Group the cells matching `include` into connected regions. Other cells
+    /// are left unlabeled.
+    pub fn regions_where<P>(&self, include: P) -> Regions
+    where
+        P: FnMut(&T) -> bool,
+    {
+        self.label_regions(include, |_, _| true)
+    }
+
+    fn label_regions<P, F>(&self, mut include: P, mut same: F) -> Regions
+    where
+        P: FnMut(&T) -> bool,
+        F: FnMut(&T, &T) -> bool,
+    {
+        let mut labels = Grid::new(vec![None; self.data.len()], self.width)
+            .with_topology(self.topology);
+        let mut groups = vec![];
+
+        for start in 0..self.data.len() {
+            if labels.data[start].is_some() || !include(&self.data[start]) {
+                continue;
+            }
+
+            let label = groups.len();
+            let cells = self
+                .flood_fill(start, |from, to| include(to) && same(from, to));
+            cells.iter().for_each(|i| labels.data[*i] = Some(label));
+            groups.push(cells);
+        }
+
+        let mut regions = Regions {
+            labels,
+            regions: Vec::with_capacity(groups.len()),
+        };
+
+        for (label, cells) in groups.into_iter().enumerate() {
+            let region = regions.region(label, cells);
+            regions.regions.push(region);
+        }
+
+        regions
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::*;
+    use crate::Topology;
+
+    #[test]
+    fn region_geometry() {
+        let grid: Grid<char> = Grid::parse_lines("AAAA\nABAA\nAAAA\nAAAA");
+        let all = grid.regions_by(|a, b| a == b);
+        let regions = &all.regions;
+
+        assert_eq!(regions.len(), 2);
+        assert_eq!(all.labels.data[5], Some(1));
+
+        let outer = &regions[0];
+        assert_eq!(outer.area(), 15);
+        assert_eq!(outer.perimeter, 20);
+        assert_eq!(outer.sides, 8);
+        assert_eq!(outer.bounds, (Pos::new(0, 0), Pos::new(3, 3)));
+
+        let inner = &regions[1];
+        assert_eq!((inner.area(), inner.perimeter, inner.sides), (1, 4, 4));
+
+        let outlines = all.boundary(0);
+        assert_eq!(outlines.len(), 2);
+        assert_eq!(
+            outlines[1],
+            vec![
+                Pos::new(1, 1),
+                Pos::new(1, 2),
+                Pos::new(2, 2),
+                Pos::new(2, 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn regions_by_predicate() {
+        let grid: Grid<char> = Grid::parse_lines("#.#\n#.#\n..#");
+        let regions = grid.regions_where(|c| *c == '#');
+
+        assert_eq!(regions.regions.len(), 2);
+        assert_eq!(regions.labels.data[1], None);
+        assert_eq!(regions.regions[1].bounds, (Pos::new(2, 0), Pos::new(2, 2)));
+        assert_eq!(grid.flood_fill(1, |_, b| *b == '.').len(), 4);
+    }
+
+    #[test]
+    fn boundary_polygons() {
+        let grid: Grid<char> = Grid::parse_lines("BAA\nABA\nAAA");
+        let regions = grid.regions_by(|a, b| a == b);
+        let a = regions.labels.data[1].unwrap();
+
+        let polygons = regions.boundary(a);
+        let corners = polygons.iter().map(Vec::len).sum::<usize>();
+        assert_eq!(corners, regions.regions[a].sides);
+        assert_eq!(
+            polygons,
+            vec![vec![
+                Pos::new(1, 0),
+                Pos::new(3, 0),
+                Pos::new(3, 3),
+                Pos::new(0, 3),
+                Pos::new(0, 1),
+                Pos::new(1, 1),
+                Pos::new(1, 2),
+                Pos::new(2, 2),
+                Pos::new(2, 1),
+                Pos::new(1, 1),
+            ]]
+        );
+    }
+
+    #[test]
+    fn boundary_across_the_wrap() {
+        let grid: Grid<char> = Grid::parse_lines("A..A\n....\n....")
+            .with_topology(Topology::TORUS);
+        let regions = grid.regions_where(|c| *c == 'A');
+
+        assert_eq!(regions.regions.len(), 1);
+        assert_eq!(regions.regions[0].perimeter, 6);
+        assert_eq!(
+            regions.boundary(0),
+            vec![
+                vec![
+                    Pos::new(0, 0),
+                    Pos::new(1, 0),
+                    Pos::new(1, 1),
+                    Pos::new(0, 1)
+                ],
+                vec![
+                    Pos::new(3, 0),
+                    Pos::new(4, 0),
+                    Pos::new(4, 1),
+                    Pos::new(3, 1)
+                ],
+            ]
+        );
+    }
+}
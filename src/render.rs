@@ -0,0 +1,292 @@
+//! Terminal rendering of a [`Grid`] with per-cell ANSI colors, overlays for
+//! paths or marked cells, rulers and a cropped viewport.
+
+use std::{collections::HashMap, fmt, io, ops::RangeInclusive};
+
+use crate::{Grid, Pos};
+
+/// One of the 8 basic terminal colors or a 24-bit color
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    Rgb(u8, u8, u8),
+}
+
+impl Color {
+    /// The SGR parameters for this color, `base` being 30 for the foreground
+    /// and 40 for the background
+    fn sgr(self, base: u8) -> String {
+        let basic = |offset: u8| (base + offset).to_string();
+
+        match self {
+            Color::Black => basic(0),
+            Color::Red => basic(1),
+            Color::Green => basic(2),
+            Color::Yellow => basic(3),
+            Color::Blue => basic(4),
+            Color::Magenta => basic(5),
+            Color::Cyan => basic(6),
+            Color::White => basic(7),
+            Color::Rgb(r, g, b) => format!("{};2;{r};{g};{b}", base + 8),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Style {
+    pub fg: Option<Color>,
+    pub bg: Option<Color>,
+    pub bold: bool,
+}
+
+impl Style {
+    pub const fn fg(color: Color) -> Self {
+        Self {
+            fg: Some(color),
+            bg: None,
+            bold: false,
+        }
+    }
+
+    pub const fn bg(color: Color) -> Self {
+        Self {
+            fg: None,
+            bg: Some(color),
+            bold: false,
+        }
+    }
+
+    pub const fn bold(self) -> Self {
+        Self { bold: true, ..self }
+    }
+
+    /// Layer `self` on top of `base`, keeping the colors `self` doesn't set
+    pub fn over(self, base: Style) -> Self {
+        Self {
+            fg: self.fg.or(base.fg),
+            bg: self.bg.or(base.bg),
+            bold: self.bold || base.bold,
+        }
+    }
+
+    fn ansi(self) -> String {
+        let codes = self
+            .bold
+            .then(|| "1".to_string())
+            .into_iter()
+            .chain(self.fg.map(|c| c.sgr(30)))
+            .chain(self.bg.map(|c| c.sgr(40)))
+            .collect::<Vec<_>>();
+
+        format!("\x1b[{}m", codes.join(";"))
+    }
+}
+
+type StyleFn<'a, T> = Box<dyn Fn(usize, &T) -> Style + 'a>;
+
+/// Builder for rendering a grid, see [`Grid::renderer`]. Implements
+/// [`fmt::Display`], so it can be printed or turned into a string directly.
+pub struct Renderer<'a, T> {
+    grid: &'a Grid<T>,
+    glyph: Box<dyn Fn(&T) -> char + 'a>,
+    style: Option<StyleFn<'a, T>>,
+    overlays: HashMap<usize, (Option<char>, Style)>,
+    rulers: bool,
+    viewport: Option<(Pos, Pos)>,
+}
+
+impl<T> Grid<T> {
+    /// Start rendering the grid, drawing each cell as `glyph` returns it
+    pub fn renderer<'a, F>(&'a self, glyph: F) -> Renderer<'a, T>
+    where
+        F: Fn(&T) -> char + 'a,
+    {
+        Renderer {
+            grid: self,
+            glyph: Box::new(glyph),
+            style: None,
+            overlays: HashMap::new(),
+            rulers: false,
+            viewport: None,
+        }
+    }
+}
+
+impl<'a, T> Renderer<'a, T> {
+    /// Color each cell by its index and value
+    pub fn style<F>(mut self, style: F) -> Self
+    where
+        F: Fn(usize, &T) -> Style + 'a,
+    {
+        self.style = Some(Box::new(style));
+        self
+    }
+
+    /// Mark `cells`, e.g. a path, drawing them as `glyph` if given and with
+    /// `style` on top of the cell's own style. Later overlays win.
+    pub fn overlay<I>(
+        mut self,
+        cells: I,
+        glyph: Option<char>,
+        style: Style,
+    ) -> Self
+    where
+        I: IntoIterator<Item = usize>,
+    {
+        for i in cells {
+            let (prev_glyph, prev_style) =
+                self.overlays.get(&i).copied().unwrap_or_default();
+            self.overlays
+                .insert(i, (glyph.or(prev_glyph), style.over(prev_style)));
+        }
+        self
+    }
+
+    /// Number the rows on the left and the columns above the grid
+    pub fn rulers(mut self) -> Self {
+        self.rulers = true;
+        self
+    }
+
+    /// Only draw the cells between `min` and `max` (both inclusive)
+    pub fn viewport(mut self, (min, max): (Pos, Pos)) -> Self {
+        self.viewport = Some((min, max));
+        self
+    }
+
+    pub fn write_to<W: io::Write>(&self, out: &mut W) -> io::Result<()> {
+        write!(out, "{self}")
+    }
+
+    /// The visible columns and rows, i.e. the part of the viewport on the
+    /// grid. `None` if none of it is.
+    fn visible(
+        &self,
+    ) -> Option<(RangeInclusive<usize>, RangeInclusive<usize>)> {
+        let size = self.grid.size();
+        let (min, max) =
+            self.viewport.unwrap_or((Pos::ZERO, size - Pos::new(1, 1)));
+
+        let axis = |min: i64, max: i64, len: i64| {
+            let (lo, hi) = (min.max(0), max.min(len - 1));
+            (lo <= hi).then_some(lo as usize..=hi as usize)
+        };
+
+        Some((axis(min.x, max.x, size.x)?, axis(min.y, max.y, size.y)?))
+    }
+}
+
+impl<T> fmt::Display for Renderer<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some((cols, rows)) = self.visible() else {
+            return Ok(());
+        };
+        let row_width = rows.end().to_string().len();
+
+        if self.rulers {
+            let digits = cols.end().to_string().len();
+
+            for place in (0..digits as u32).rev() {
+                write!(f, "{:row_width$} ", "")?;
+                for col in cols.clone() {
+                    let power = 10usize.pow(place);
+                    if col >= power || place == 0 {
+                        write!(f, "{}", col / power % 10)?;
+                    } else {
+                        write!(f, " ")?;
+                    }
+                }
+                writeln!(f)?;
+            }
+        }
+
+        for row in rows {
+            if self.rulers {
+                write!(f, "{row:>row_width$} ")?;
+            }
+
+            for col in cols.clone() {
+                let i = self.grid.to_index(col, row);
+                let value = &self.grid.data[i];
+
+                let base = self
+                    .style
+                    .as_ref()
+                    .map(|s| s(i, value))
+                    .unwrap_or_default();
+                let (glyph, style) = match self.overlays.get(&i) {
+                    Some((glyph, style)) => (*glyph, style.over(base)),
+                    None => (None, base),
+                };
+                let glyph = glyph.unwrap_or_else(|| (self.glyph)(value));
+
+                if style == Style::default() {
+                    write!(f, "{glyph}")?;
+                } else {
+                    write!(f, "{}{glyph}\x1b[0m", style.ansi())?;
+                }
+            }
+
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_with_overlay() {
+        let grid: Grid<char> = Grid::parse_lines("#..\n...\n..#");
+        let rendered = grid
+            .renderer(|c| *c)
+            .overlay([1, 4, 7], Some('O'), Style::default())
+            .to_string();
+
+        assert_eq!(rendered, "#O.\n.O.\n.O#\n");
+    }
+
+    #[test]
+    fn colors() {
+        let grid: Grid<char> = Grid::parse_lines("ab");
+        let rendered = grid
+            .renderer(|c| *c)
+            .style(|_, c| match c {
+                'a' => Style::fg(Color::Red),
+                _ => Style::default(),
+            })
+            .overlay([0], None, Style::bg(Color::Rgb(1, 2, 3)).bold())
+            .to_string();
+
+        assert_eq!(rendered, "\x1b[1;31;48;2;1;2;3ma\x1b[0mb\n");
+    }
+
+    #[test]
+    fn rulers_and_viewport() {
+        let grid = Grid::new(vec!['.'; 12 * 11], 12);
+        let rendered = grid
+            .renderer(|c| *c)
+            .rulers()
+            .viewport((Pos::new(8, 9), Pos::new(20, 20)))
+            .to_string();
+
+        assert_eq!(rendered, "     11\n   8901\n 9 ....\n10 ....\n");
+
+        let outside = grid
+            .renderer(|c| *c)
+            .rulers()
+            .viewport((Pos::new(12, 0), Pos::new(20, 5)))
+            .to_string();
+        assert_eq!(outside, "");
+    }
+}
@@ -0,0 +1,51 @@
+//! Seeded randomness for solutions. The runner picks (or replays) a seed for
+//! each run and every call to [`rng`] starts over from it, so repeated bench
+//! samples see the same random sequence.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use once_cell::sync::OnceCell;
+use rand::{rngs::StdRng, SeedableRng};
+
+/// Seed used when no runner set one, e.g. in tests
+pub const DEFAULT_SEED: u64 = 2024;
+
+static SEED: OnceCell<u64> = OnceCell::new();
+static USED: AtomicBool = AtomicBool::new(false);
+
+/// Set the seed for this process. Returns false if a seed was already set or
+/// used.
+pub fn set_seed(seed: u64) -> bool {
+    SEED.set(seed).is_ok()
+}
+
+/// The seed [`rng`] starts from
+pub fn seed() -> u64 {
+    *SEED.get_or_init(|| DEFAULT_SEED)
+}
+
+/// Check if a solution requested an rng since the last call, resetting the
+/// flag.
+pub fn take_used() -> bool {
+    USED.swap(false, Ordering::Relaxed)
+}
+
+/// Get a fresh rng seeded with the seed of this run
+pub fn rng() -> StdRng {
+    USED.store(true, Ordering::Relaxed);
+    StdRng::seed_from_u64(seed())
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use rand::Rng;
+
+    use super::rng;
+
+    #[test]
+    fn rng_restarts_from_seed() {
+        let first = rng().gen::<u64>();
+        let second = rng().gen::<u64>();
+        assert_eq!(first, second);
+    }
+}
@@ -0,0 +1,359 @@
+//! Shortest path searches over any state space. The graph is described by a
+//! `neighbors` closure, so the same functions work on grid indices, on
+//! `(index, facing)` pairs or on anything else that is `Clone + Eq + Hash`.
+
+use std::{
+    cmp::Reverse,
+    collections::{hash_map::Entry, HashMap, HashSet, VecDeque},
+    hash::Hash,
+};
+
+use priority_queue::PriorityQueue;
+
+use crate::lattice::Lattice;
+
+/// Breadth first search from `start` to the first state matching `is_goal`.
+/// Every step costs 1. Returns the goal state and its distance.
+pub fn bfs<S, I, N, G>(
+    start: S,
+    mut neighbors: N,
+    mut is_goal: G,
+) -> Option<(S, usize)>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+    N: FnMut(&S) -> I,
+    G: FnMut(&S) -> bool,
+{
+    let mut seen = HashSet::from([start.clone()]);
+    let mut queue = VecDeque::from([(start, 0)]);
+
+    while let Some((curr, dist)) = queue.pop_front() {
+        if is_goal(&curr) {
+            return Some((curr, dist));
+        }
+
+        queue.extend(
+            neighbors(&curr)
+                .into_iter()
+                .filter(|n| seen.insert(n.clone()))
+                .map(|n| (n, dist + 1)),
+        );
+    }
+
+    None
+}
+
+/// Breadth first search of everything reachable from `start`, returning the
+/// distance to each reached state
+pub fn bfs_distances<S, I, N>(start: S, mut neighbors: N) -> HashMap<S, usize>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+    N: FnMut(&S) -> I,
+{
+    let mut dist = HashMap::from([(start.clone(), 0)]);
+    let mut queue = VecDeque::from([start]);
+
+    while let Some(curr) = queue.pop_front() {
+        let next_dist = dist[&curr] + 1;
+
+        for n in neighbors(&curr) {
+            if let Entry::Vacant(entry) = dist.entry(n.clone()) {
+                entry.insert(next_dist);
+                queue.push_back(n);
+            }
+        }
+    }
+
+    dist
+}
+
+/// Dijkstra's algorithm from all of `starts` to the first state matching
+/// `is_goal`. `neighbors` yields each next state with the cost of the step.
+pub fn dijkstra<S, I, N, G>(
+    starts: impl IntoIterator<Item = S>,
+    neighbors: N,
+    is_goal: G,
+) -> Option<(S, usize)>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = (S, usize)>,
+    N: FnMut(&S) -> I,
+    G: FnMut(&S) -> bool,
+{
+    astar(starts, neighbors, |_| 0, is_goal)
+}
+
+/// A* search from all of `starts` to the first state matching `is_goal`.
+/// `heuristic` must never overestimate the remaining cost, e.g.
+/// [`Grid::min_dist`](crate::Grid::min_dist) to the goal when every step
+/// costs at least 1.
+pub fn astar<S, I, N, H, G>(
+    starts: impl IntoIterator<Item = S>,
+    mut neighbors: N,
+    mut heuristic: H,
+    mut is_goal: G,
+) -> Option<(S, usize)>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = (S, usize)>,
+    N: FnMut(&S) -> I,
+    H: FnMut(&S) -> usize,
+    G: FnMut(&S) -> bool,
+{
+    let mut dist = HashMap::new();
+    let mut queue = PriorityQueue::new();
+
+    for start in starts {
+        dist.insert(start.clone(), 0);
+        let estimate = heuristic(&start);
+        queue.push(start, Reverse(estimate));
+    }
+
+    while let Some((curr, _)) = queue.pop() {
+        let curr_dist = dist[&curr];
+
+        if is_goal(&curr) {
+            return Some((curr, curr_dist));
+        }
+
+        for (n, cost) in neighbors(&curr) {
+            let next_dist = curr_dist + cost;
+
+            if dist.get(&n).is_none_or(|d| next_dist < *d) {
+                dist.insert(n.clone(), next_dist);
+                let estimate = next_dist + heuristic(&n);
+                queue.push_increase(n, Reverse(estimate));
+            }
+        }
+    }
+
+    None
+}
+
+/// A* between two cells of any [`Lattice`], using [`Lattice::distance`] as
+/// the heuristic. Returns the cost of the cheapest path.
+pub fn grid_astar<L, I, N>(
+    grid: &L,
+    start: usize,
+    goal: usize,
+    neighbors: N,
+) -> Option<usize>
+where
+    L: Lattice,
+    I: IntoIterator<Item = (usize, usize)>,
+    N: FnMut(&usize) -> I,
+{
+    astar(
+        [start],
+        neighbors,
+        |i| grid.distance(*i, goal),
+        |i| *i == goal,
+    )
+    .map(|(_, dist)| dist)
+}
+
+/// Every cheapest path from the starts to the goals of an
+/// [`all_shortest_paths`] search
+#[derive(Debug, Clone)]
+pub struct ShortestPaths<S> {
+    /// Cost of the cheapest path
+    pub cost: usize,
+    /// Goal states reached at `cost`
+    pub goals: Vec<S>,
+    /// Cheapest known cost of each visited state
+    pub dist: HashMap<S, usize>,
+    /// The states each state is reached from at its cheapest cost
+    pub predecessors: HashMap<S, Vec<S>>,
+}
+
+impl<S> ShortestPaths<S>
+where
+    S: Clone + Eq + Hash,
+{
+    /// All states that lie on at least one cheapest path, goals and starts
+    /// included
+    pub fn on_any_path(&self) -> HashSet<S> {
+        let mut seen = self.goals.iter().cloned().collect::<HashSet<_>>();
+        let mut stack = self.goals.clone();
+
+        while let Some(curr) = stack.pop() {
+            for prev in self.predecessors.get(&curr).into_iter().flatten() {
+                if seen.insert(prev.clone()) {
+                    stack.push(prev.clone());
+                }
+            }
+        }
+
+        seen
+    }
+
+    /// Number of distinct cheapest paths, summed over all goals. Steps must
+    /// cost more than 0.
+    pub fn count_paths(&self) -> usize {
+        // predecessors are cheaper than the states they lead to, so in order
+        // of cost every state is counted after all of its predecessors
+        let mut states = self.on_any_path().into_iter().collect::<Vec<_>>();
+        states.sort_by_key(|s| self.dist[s]);
+
+        let mut counts = HashMap::new();
+
+        for state in states {
+            let n = match self.predecessors.get(&state) {
+                Some(prev) => prev.iter().map(|p| counts[p]).sum(),
+                None => 1,
+            };
+            counts.insert(state, n);
+        }
+
+        self.goals.iter().map(|goal| counts[goal]).sum()
+    }
+
+    /// One cheapest path from a start to the first goal
+    pub fn path(&self) -> Vec<S> {
+        let mut path = self.goals[..1].to_vec();
+
+        while let Some(prev) = self
+            .predecessors
+            .get(path.last().unwrap())
+            .and_then(|p| p.first())
+        {
+            path.push(prev.clone());
+        }
+
+        path.reverse();
+        path
+    }
+}
+
+/// Dijkstra's algorithm that keeps every predecessor at the cheapest cost
+/// instead of just one, so all tied cheapest paths can be recovered. All goal
+/// states reachable at the cheapest cost are found.
+pub fn all_shortest_paths<S, I, N, G>(
+    starts: impl IntoIterator<Item = S>,
+    mut neighbors: N,
+    mut is_goal: G,
+) -> Option<ShortestPaths<S>>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = (S, usize)>,
+    N: FnMut(&S) -> I,
+    G: FnMut(&S) -> bool,
+{
+    let mut dist = HashMap::new();
+    let mut predecessors: HashMap<S, Vec<S>> = HashMap::new();
+    let mut queue = PriorityQueue::new();
+    let mut goals = vec![];
+    let mut best = None;
+
+    for start in starts {
+        dist.insert(start.clone(), 0);
+        queue.push(start, Reverse(0));
+    }
+
+    while let Some((curr, Reverse(curr_dist))) = queue.pop() {
+        if best.is_some_and(|b| curr_dist > b) {
+            break;
+        }
+
+        if is_goal(&curr) {
+            best = Some(curr_dist);
+            goals.push(curr);
+            continue;
+        }
+
+        for (n, cost) in neighbors(&curr) {
+            let next_dist = curr_dist + cost;
+
+            match dist.get(&n) {
+                Some(d) if next_dist > *d => {}
+                Some(d) if next_dist == *d => {
+                    predecessors.entry(n).or_default().push(curr.clone());
+                }
+                _ => {
+                    dist.insert(n.clone(), next_dist);
+                    predecessors.insert(n.clone(), vec![curr.clone()]);
+                    queue.push_increase(n, Reverse(next_dist));
+                }
+            }
+        }
+    }
+
+    best.map(|cost| ShortestPaths {
+        cost,
+        goals,
+        dist,
+        predecessors,
+    })
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::*;
+    use crate::Grid;
+
+    const MAZE: &str = "\
+S..#
+.#..
+...E";
+
+    fn open_neighbors(grid: &Grid<char>, i: usize) -> Vec<(usize, usize)> {
+        grid.neighbors(i)
+            .filter(|(_, n)| grid.data[*n] != '#')
+            .map(|(_, n)| (n, 1))
+            .collect()
+    }
+
+    #[test]
+    fn bfs_and_dijkstra_agree() {
+        let grid: Grid<char> = Grid::parse_lines(MAZE);
+        let end = grid.data.len() - 1;
+        let plain =
+            |i: &usize| open_neighbors(&grid, *i).into_iter().map(|(n, _)| n);
+
+        assert_eq!(bfs(0, plain, |i| *i == end), Some((end, 5)));
+        assert_eq!(bfs_distances(0, plain)[&end], 5);
+        assert_eq!(
+            dijkstra([0], |i| open_neighbors(&grid, *i), |i| *i == end),
+            Some((end, 5))
+        );
+        assert_eq!(
+            grid_astar(&grid, 0, end, |i| open_neighbors(&grid, *i)),
+            Some(5)
+        );
+        assert_eq!(bfs(0, plain, |i| *i == 3), None);
+    }
+
+    #[test]
+    fn weighted_edges() {
+        // 0 -> 1 -> 3 costs 2, 0 -> 2 -> 3 costs 2, 0 -> 3 costs 5
+        let edges = |s: &u8| match s {
+            0 => vec![(1, 1), (2, 1), (3, 5)],
+            1 | 2 => vec![(3, 1)],
+            _ => vec![],
+        };
+
+        let paths = all_shortest_paths([0u8], edges, |s| *s == 3).unwrap();
+        assert_eq!(paths.cost, 2);
+        assert_eq!(paths.count_paths(), 2);
+        assert_eq!(paths.on_any_path().len(), 4);
+        assert_eq!(paths.path().len(), 3);
+        assert_eq!(dijkstra([0u8], edges, |s| *s == 3), Some((3, 2)));
+    }
+
+    #[test]
+    fn long_paths() {
+        // deep enough to overflow the stack if counted recursively, with two
+        // ways past every 5000th state
+        let edges = |s: &u32| match s % 5_000 {
+            0 => vec![(s + 1, 1), (s + 2, 2)],
+            _ => vec![(s + 1, 1)],
+        };
+
+        let paths =
+            all_shortest_paths([0u32], edges, |s| *s == 100_000).unwrap();
+        assert_eq!(paths.cost, 100_000);
+        assert_eq!(paths.count_paths(), 1 << 20);
+    }
+}
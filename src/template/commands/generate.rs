@@ -0,0 +1,61 @@
+use std::{fs, path::PathBuf, process};
+
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::generate::generator_for;
+use crate::template::config;
+use crate::Day;
+
+pub fn get_path_for_generated(day: Day, size: usize) -> PathBuf {
+    config::get().data.generated.join(format!("{day}-{size}.txt"))
+}
+
+pub fn handle(day: Day, sizes: &[usize], seed: Option<u64>) {
+    let Some(generator) = generator_for(day) else {
+        eprintln!("No input generator available for day {day}.");
+        process::exit(1);
+    };
+
+    if sizes.is_empty() {
+        eprintln!(
+            "No sizes specified. Pass one or more `--size <n>` ({}).",
+            generator.size_hint()
+        );
+        process::exit(1);
+    }
+
+    if let Err(e) = fs::create_dir_all(&config::get().data.generated) {
+        eprintln!("Failed to create output directory: {e}");
+        process::exit(1);
+    }
+
+    let mut rng = match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+
+    for &size in sizes {
+        let path = get_path_for_generated(day, size);
+        let input = generator.generate(size, &mut rng);
+
+        match fs::write(&path, input) {
+            Ok(()) => {
+                println!(
+                    "Generated input with {} {size} at \"{}\"",
+                    generator.size_hint(),
+                    path.display()
+                );
+            }
+            Err(e) => {
+                eprintln!("Failed to write generated input: {e}");
+                process::exit(1);
+            }
+        }
+    }
+
+    println!("---");
+    println!(
+        "🎄 Type `cargo solve {day} --release --time --input {}` to time your solution on it.",
+        get_path_for_generated(day, sizes[sizes.len() - 1]).display()
+    );
+}
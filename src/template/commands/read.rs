@@ -1,11 +1,31 @@
-use std::process;
+use std::{env, fs, path::PathBuf, process};
 
-use crate::template::aoc_cli;
+use crate::template::config;
+use crate::template::puzzle::Puzzle;
+use crate::template::{aoc_cli, ANSI_ITALIC, ANSI_RESET};
 use crate::Day;
 
-pub fn handle(day: Day) {
+/// Where to put an example block taken from the puzzle description
+pub struct SaveExample {
+    /// 1-based index of the code block in the description
+    pub block: usize,
+    /// Write to `NN-<number>.txt` instead of `NN.txt`
+    pub number: Option<u32>,
+    /// Replace an existing non-empty example file
+    pub force: bool,
+}
+
+fn terminal_width() -> usize {
+    env::var("COLUMNS")
+        .ok()
+        .and_then(|c| c.parse::<usize>().ok())
+        .unwrap_or(80)
+        .min(100)
+}
+
+fn read_online(day: Day) {
     if aoc_cli::check().is_err() {
-        eprintln!("command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it.");
+        eprintln!("Puzzle description not downloaded yet and command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it.");
         process::exit(1);
     }
 
@@ -14,3 +34,75 @@ pub fn handle(day: Day) {
         process::exit(1);
     };
 }
+
+fn save_example(day: Day, puzzle: &Puzzle, save: &SaveExample) {
+    let Some(code) = puzzle.code_blocks().nth(save.block.saturating_sub(1)) else {
+        eprintln!(
+            "There is no example {} in the description of day {day} (found {}).",
+            save.block,
+            puzzle.code_blocks().count()
+        );
+        process::exit(1);
+    };
+
+    let examples = &config::get().data.examples;
+    let path: PathBuf = match save.number {
+        Some(number) => examples.join(format!("{day}-{number}.txt")),
+        None => examples.join(format!("{day}.txt")),
+    };
+
+    let is_empty = fs::read_to_string(&path).map_or(true, |content| content.trim().is_empty());
+    if !is_empty && !save.force {
+        eprintln!(
+            "Example file \"{}\" is not empty. Pass --force to replace it.",
+            path.display()
+        );
+        process::exit(1);
+    }
+
+    match fs::write(&path, code) {
+        Ok(()) => println!("🎄 Wrote example {} to \"{}\".", save.block, path.display()),
+        Err(e) => {
+            eprintln!("Failed to write example file: {e}");
+            process::exit(1);
+        }
+    }
+}
+
+pub fn handle(day: Day, part: Option<usize>, width: Option<usize>, save: Option<SaveExample>) {
+    let path = config::get().puzzle_path(day);
+
+    let Ok(markdown) = fs::read_to_string(&path) else {
+        // fall back to aoc-cli when the description has not been downloaded.
+        if save.is_some() {
+            eprintln!(
+                "Puzzle description \"{}\" not found. Run `cargo download {day}` first.",
+                path.display()
+            );
+            process::exit(1);
+        }
+        read_online(day);
+        return;
+    };
+
+    let puzzle = Puzzle::parse(&markdown);
+
+    if let Some(save) = save {
+        save_example(day, &puzzle, &save);
+        return;
+    }
+
+    if part.is_some_and(|p| p > puzzle.parts.len()) {
+        println!("{ANSI_ITALIC}Part two is still locked. Solve part one and run `cargo download {day}` again to unlock it.{ANSI_RESET}");
+        return;
+    }
+
+    println!(
+        "{}",
+        puzzle.render(part, width.unwrap_or_else(terminal_width))
+    );
+
+    if part.is_none() && puzzle.parts.len() < 2 {
+        println!("{ANSI_ITALIC}Part two is still locked.{ANSI_RESET}");
+    }
+}
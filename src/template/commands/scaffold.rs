@@ -1,9 +1,12 @@
 use std::{
-    fs::{File, OpenOptions},
+    borrow::Cow,
+    fs::{self, File, OpenOptions},
     io::Write,
+    path::{Path, PathBuf},
     process,
 };
 
+use crate::template::config;
 use crate::Day;
 
 const MODULE_TEMPLATE: &str = r#"advent_of_code::solution!(DAY_NUMBER);
@@ -34,18 +37,40 @@ mod tests {
 }
 "#;
 
-fn safe_create_file(path: &str) -> Result<File, std::io::Error> {
+fn safe_create_file(path: &Path) -> Result<File, std::io::Error> {
     OpenOptions::new().write(true).create_new(true).open(path)
 }
 
-fn create_file(path: &str) -> Result<File, std::io::Error> {
-    OpenOptions::new().write(true).create(true).open(path)
+fn create_file(path: &Path) -> Result<File, std::io::Error> {
+    OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(path)
 }
 
-pub fn handle(day: Day) {
-    let input_path = format!("data/inputs/{day}.txt");
-    let example_path = format!("data/examples/{day}.txt");
-    let module_path = format!("src/bin/{day}.rs");
+/// Get the module template, preferring `template` over the one configured in `aoc.toml` over the
+/// built-in one.
+fn load_template(template: Option<&Path>) -> Cow<'static, str> {
+    let Some(path) = template.or(config::get().scaffold.template.as_deref()) else {
+        return Cow::Borrowed(MODULE_TEMPLATE);
+    };
+
+    match fs::read_to_string(path) {
+        Ok(template) => Cow::Owned(template),
+        Err(e) => {
+            eprintln!("Failed to read module template \"{}\": {e}", path.display());
+            process::exit(1);
+        }
+    }
+}
+
+pub fn handle(day: Day, template: Option<&Path>) {
+    let config = config::get();
+    let input_path = config.input_path(day);
+    let example_path = config.example_path(day);
+    let module_path = PathBuf::from(format!("src/bin/{day}.rs"));
+    let template = load_template(template);
 
     let mut file = match safe_create_file(&module_path) {
         Ok(file) => file,
@@ -56,12 +81,12 @@ pub fn handle(day: Day) {
     };
 
     match file.write_all(
-        MODULE_TEMPLATE
+        template
             .replace("DAY_NUMBER", &day.into_inner().to_string())
             .as_bytes(),
     ) {
         Ok(()) => {
-            println!("Created module file \"{}\"", &module_path);
+            println!("Created module file \"{}\"", module_path.display());
         }
         Err(e) => {
             eprintln!("Failed to write module contents: {e}");
@@ -71,7 +96,7 @@ pub fn handle(day: Day) {
 
     match create_file(&input_path) {
         Ok(_) => {
-            println!("Created empty input file \"{}\"", &input_path);
+            println!("Created empty input file \"{}\"", input_path.display());
         }
         Err(e) => {
             eprintln!("Failed to create input file: {e}");
@@ -81,7 +106,7 @@ pub fn handle(day: Day) {
 
     match create_file(&example_path) {
         Ok(_) => {
-            println!("Created empty example file \"{}\"", &example_path);
+            println!("Created empty example file \"{}\"", example_path.display());
         }
         Err(e) => {
             eprintln!("Failed to create example file: {e}");
@@ -0,0 +1,243 @@
+use std::{path::Path, process};
+
+use crate::template::{ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
+use crate::{all_days, Day};
+
+use super::all::get_path_for_bin;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Passed,
+    Failed,
+    Ignored,
+}
+
+/// Test results for one target. The `tests::test_part_one` and
+/// `tests::test_part_two` tests of a day count for their part, everything
+/// else (including all lib tests) is other.
+#[derive(Debug, Default, Clone)]
+pub struct Summary {
+    pub part_1: Vec<Outcome>,
+    pub part_2: Vec<Outcome>,
+    pub other: Vec<Outcome>,
+    /// Set if the test binary could not be built or run at all
+    pub broken: bool,
+}
+
+impl Summary {
+    fn record(&mut self, name: &str, outcome: Outcome, by_part: bool) {
+        match name {
+            "tests::test_part_one" if by_part => self.part_1.push(outcome),
+            "tests::test_part_two" if by_part => self.part_2.push(outcome),
+            _ => self.other.push(outcome),
+        }
+    }
+
+    fn outcomes(&self) -> impl Iterator<Item = &'_ Outcome> + '_ {
+        self.part_1.iter().chain(&self.part_2).chain(&self.other)
+    }
+
+    pub fn failed(&self) -> usize {
+        self.outcomes().filter(|o| **o == Outcome::Failed).count()
+    }
+
+    pub fn passed(&self) -> usize {
+        self.outcomes().filter(|o| **o == Outcome::Passed).count()
+    }
+
+    pub fn is_ok(&self) -> bool {
+        !self.broken && self.failed() == 0
+    }
+}
+
+pub fn handle(days: &[Day], is_release: bool) {
+    let days = if days.is_empty() { all_days().collect() } else { days.to_vec() };
+
+    let mut rows: Vec<(String, Summary)> = vec![];
+
+    let lib = child_commands::run_tests(&["--lib", "--features", "test_lib"], false, is_release);
+    rows.push(("lib".into(), lib));
+
+    for day in days {
+        // skip days that have not been scaffolded yet.
+        if !Path::new(&get_path_for_bin(day)).exists() {
+            continue;
+        }
+
+        let summary = child_commands::run_tests(&["--bin", &day.to_string()], true, is_release);
+        rows.push((format!("Day {day}"), summary));
+    }
+
+    print_grid(&rows);
+
+    if rows.iter().any(|(_, summary)| !summary.is_ok()) {
+        process::exit(1);
+    }
+}
+
+fn cell(outcomes: &[Outcome]) -> String {
+    if outcomes.is_empty() {
+        "·".into()
+    } else if outcomes.contains(&Outcome::Failed) {
+        "✖".into()
+    } else if outcomes.iter().all(|o| *o == Outcome::Ignored) {
+        "-".into()
+    } else {
+        "✔".into()
+    }
+}
+
+fn print_grid(rows: &[(String, Summary)]) {
+    println!();
+    println!("{ANSI_BOLD}{:<8} {:^6} {:^6} {:^6}{ANSI_RESET}", "", "Part 1", "Part 2", "Other");
+
+    for (name, summary) in rows {
+        if summary.broken {
+            println!("{name:<8} {ANSI_ITALIC}failed to build or run{ANSI_RESET}");
+            continue;
+        }
+
+        println!(
+            "{name:<8} {:^6} {:^6} {:^6}",
+            cell(&summary.part_1),
+            cell(&summary.part_2),
+            cell(&summary.other)
+        );
+    }
+
+    let passed = rows.iter().map(|(_, s)| s.passed()).sum::<usize>();
+    let failed = rows.iter().map(|(_, s)| s.failed()).sum::<usize>();
+    let broken = rows.iter().filter(|(_, s)| s.broken).count();
+
+    println!();
+    print!("{ANSI_BOLD}Summary:{ANSI_RESET} {passed} passed, {failed} failed");
+    if broken > 0 {
+        print!(", {broken} broken");
+    }
+    println!();
+}
+
+/// Test runs happen in child `cargo test` processes. This module invokes them
+/// and parses the libtest output.
+mod child_commands {
+    use super::{Outcome, Summary};
+    use std::process::{Command, Stdio};
+
+    /// Run the tests of `target`, counting them by part if `by_part` is set
+    pub fn run_tests(target: &[&str], by_part: bool, is_release: bool) -> Summary {
+        let mut args = vec!["test", "--quiet"];
+        args.extend(target);
+
+        if is_release {
+            args.push("--release");
+        }
+
+        // libtest prints one line per test with `--format pretty`.
+        args.extend(["--", "--format", "pretty", "--color", "never"]);
+
+        let output = match Command::new("cargo")
+            .args(&args)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .output()
+        {
+            Ok(output) => output,
+            Err(e) => {
+                eprintln!("Failed to run cargo test: {e}");
+                return Summary {
+                    broken: true,
+                    ..Summary::default()
+                };
+            }
+        };
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let mut summary = parse_test_output(&stdout, by_part);
+
+        if !output.status.success() {
+            // forward failure details (panic messages, build errors).
+            eprint!("{}", String::from_utf8_lossy(&output.stderr));
+            print_failures(&stdout);
+            summary.broken |= summary.failed() == 0;
+        }
+
+        summary
+    }
+
+    pub fn parse_test_output(output: &str, by_part: bool) -> Summary {
+        let mut summary = Summary::default();
+
+        output
+            .lines()
+            .filter_map(|line| {
+                let rest = line.strip_prefix("test ")?;
+                let (name, result) = rest.rsplit_once(" ... ")?;
+                let outcome = match result.trim() {
+                    "ok" => Outcome::Passed,
+                    "FAILED" => Outcome::Failed,
+                    r if r.starts_with("ignored") => Outcome::Ignored,
+                    _ => return None,
+                };
+                Some((name.trim_end_matches(" - should panic"), outcome))
+            })
+            .for_each(|(name, outcome)| summary.record(name, outcome, by_part));
+
+        summary
+    }
+
+    fn print_failures(output: &str) {
+        output
+            .split("\n---- ")
+            .skip(1)
+            .map(|section| section.split("\nfailures:").next().unwrap_or(section))
+            .for_each(|section| eprintln!("---- {}", section.trim_end()));
+    }
+
+    #[cfg(feature = "test_lib")]
+    mod tests {
+        use super::parse_test_output;
+        use crate::template::commands::test::Outcome;
+
+        #[test]
+        fn test_day_output() {
+            let res = parse_test_output(
+                "
+running 3 tests
+test tests::test_part_one ... ok
+test tests::test_part_two ... FAILED
+test tests::test_record_part_two ... ok
+
+failures:
+
+failures:
+    tests::test_part_two
+
+test result: FAILED. 1 passed; 1 failed; 0 ignored",
+                true,
+            );
+            assert_eq!(res.part_1, vec![Outcome::Passed]);
+            assert_eq!(res.part_2, vec![Outcome::Failed]);
+            assert_eq!(res.other, vec![Outcome::Passed]);
+            assert_eq!(res.failed(), 1);
+        }
+
+        #[test]
+        fn test_lib_output() {
+            let res = parse_test_output(
+                "
+running 4 tests
+test day::tests::all_days_iterator ... ok
+test template::readme_benchmarks::tests::errors_if_marker_not_present - should panic ... ok
+test template::puzzle::tests::locked_part_two ... ok
+test generate::tests::slow ... ignored, takes a while",
+                false,
+            );
+            assert!(res.part_1.is_empty() && res.part_2.is_empty());
+            assert_eq!(
+                res.other,
+                vec![Outcome::Passed, Outcome::Passed, Outcome::Passed, Outcome::Ignored]
+            );
+            assert!(res.is_ok());
+        }
+    }
+}
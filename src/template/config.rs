@@ -0,0 +1,256 @@
+/// Project-wide settings loaded from `aoc.toml`.
+///
+/// Every key is optional and falls back to the defaults below. The file is
+/// looked up at the path passed with `--config`, then in the `AOC_CONFIG`
+/// environment variable, then as `aoc.toml` in the working directory.
+use std::{
+    env, fmt, fs, io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use once_cell::sync::OnceCell;
+use serde::Deserialize;
+
+use crate::Day;
+
+pub const CONFIG_FILE: &str = "aoc.toml";
+pub const CONFIG_ENV: &str = "AOC_CONFIG";
+
+static CONFIG: OnceCell<Config> = OnceCell::new();
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Puzzle year passed to aoc-cli. The `AOC_YEAR` env var takes precedence.
+    pub year: Option<u16>,
+    pub data: DataConfig,
+    pub bench: BenchConfig,
+    pub readme: ReadmeConfig,
+    /// Seconds a single solution part may run before it is aborted.
+    pub timeout: Option<u64>,
+    pub scaffold: ScaffoldConfig,
+    pub submit: SubmitConfig,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DataConfig {
+    pub inputs: PathBuf,
+    pub examples: PathBuf,
+    pub puzzles: PathBuf,
+    pub generated: PathBuf,
+}
+
+impl Default for DataConfig {
+    fn default() -> Self {
+        Self {
+            inputs: "data/inputs".into(),
+            examples: "data/examples".into(),
+            puzzles: "data/puzzles".into(),
+            generated: "data/generated".into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BenchConfig {
+    /// Approximate time spent benching each part, in milliseconds.
+    pub budget_ms: u64,
+    pub min_samples: u64,
+    pub max_samples: u64,
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        Self {
+            budget_ms: 1000,
+            min_samples: 10,
+            max_samples: 10000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ReadmeConfig {
+    pub path: PathBuf,
+    pub marker: String,
+}
+
+impl Default for ReadmeConfig {
+    fn default() -> Self {
+        Self {
+            path: "README.md".into(),
+            marker: "<!--- benchmarking table --->".into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ScaffoldConfig {
+    /// File used as the solution module template instead of the built-in one.
+    /// `DAY_NUMBER` is replaced with the day.
+    pub template: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SubmitPolicy {
+    /// Submit whenever `--submit` is passed.
+    #[default]
+    Always,
+    /// Only submit from optimized builds.
+    ReleaseOnly,
+    /// Never submit, even with `--submit`.
+    Never,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SubmitConfig {
+    pub policy: SubmitPolicy,
+}
+
+#[derive(Debug)]
+pub enum Error {
+    IO(PathBuf, io::Error),
+    Parser(PathBuf, toml::de::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::IO(path, e) => write!(f, "could not read {}: {e}", path.display()),
+            Error::Parser(path, e) => write!(f, "invalid config in {}: {e}", path.display()),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl Config {
+    /// Load the config from `path`, or from the default locations if `None`.
+    /// A missing default file yields the default config.
+    pub fn load(path: Option<&Path>) -> Result<Self, Error> {
+        let (path, required) = match path {
+            Some(path) => (path.to_path_buf(), true),
+            None => match env::var_os(CONFIG_ENV) {
+                Some(path) => (path.into(), true),
+                None => (CONFIG_FILE.into(), false),
+            },
+        };
+
+        match fs::read_to_string(&path) {
+            Ok(content) => Self::parse(&content).map_err(|e| Error::Parser(path, e)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound && !required => Ok(Self::default()),
+            Err(e) => Err(Error::IO(path, e)),
+        }
+    }
+
+    pub fn parse(content: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(content)
+    }
+
+    /// The puzzle year, preferring the `AOC_YEAR` env var over the file.
+    pub fn year(&self) -> Option<u16> {
+        env::var("AOC_YEAR")
+            .ok()
+            .and_then(|x| x.parse().ok())
+            .or(self.year)
+    }
+
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout.map(Duration::from_secs)
+    }
+
+    pub fn input_path(&self, day: Day) -> PathBuf {
+        self.data.inputs.join(format!("{day}.txt"))
+    }
+
+    pub fn example_path(&self, day: Day) -> PathBuf {
+        self.data.examples.join(format!("{day}.txt"))
+    }
+
+    pub fn puzzle_path(&self, day: Day) -> PathBuf {
+        self.data.puzzles.join(format!("{day}.md"))
+    }
+
+    /// Resolve one of the data folders by its short name (`inputs`,
+    /// `examples`, ...). Unknown names are looked up below `data/`.
+    pub fn data_dir(&self, folder: &str) -> PathBuf {
+        match folder {
+            "inputs" => self.data.inputs.clone(),
+            "examples" => self.data.examples.clone(),
+            "puzzles" => self.data.puzzles.clone(),
+            "generated" => self.data.generated.clone(),
+            other => Path::new("data").join(other),
+        }
+    }
+}
+
+/// Install `config` as the process-wide config. Has no effect if the config
+/// was already read.
+pub fn init(config: Config) {
+    let _ = CONFIG.set(config);
+}
+
+/// Get the process-wide config, loading it from the default locations on first
+/// use. Exits the process if the config file is invalid.
+pub fn get() -> &'static Config {
+    CONFIG.get_or_init(|| match Config::load(None) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            std::process::exit(1);
+        }
+    })
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Config, SubmitPolicy};
+
+    #[test]
+    fn empty_config_uses_defaults() {
+        let config = Config::parse("").unwrap();
+        assert_eq!(config.year, None);
+        assert_eq!(config.bench.budget_ms, 1000);
+        assert_eq!(config.readme.marker, "<!--- benchmarking table --->");
+        assert_eq!(config.submit.policy, SubmitPolicy::Always);
+    }
+
+    #[test]
+    fn partial_config() {
+        let config = Config::parse(
+            r#"
+year = 2023
+timeout = 30
+
+[bench]
+max_samples = 100
+
+[data]
+inputs = "puzzles/in"
+
+[submit]
+policy = "release-only"
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.year, Some(2023));
+        assert_eq!(config.timeout.unwrap(), 30);
+        assert_eq!(config.bench.max_samples, 100);
+        assert_eq!(config.bench.min_samples, 10);
+        assert_eq!(config.data_dir("inputs").to_str(), Some("puzzles/in"));
+        assert_eq!(config.data_dir("examples").to_str(), Some("data/examples"));
+        assert_eq!(config.submit.policy, SubmitPolicy::ReleaseOnly);
+    }
+
+    #[test]
+    fn unknown_keys_are_rejected() {
+        assert!(Config::parse("yaer = 2023").is_err());
+    }
+}
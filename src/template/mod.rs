@@ -1,34 +1,46 @@
 use crate::Day;
-use std::{env, fs};
+use std::fs;
 
 pub mod aoc_cli;
 pub mod commands;
+pub mod config;
+pub mod puzzle;
 pub mod readme_benchmarks;
 pub mod runner;
 
 pub const ANSI_ITALIC: &str = "\x1b[3m";
 pub const ANSI_BOLD: &str = "\x1b[1m";
+pub const ANSI_CODE: &str = "\x1b[36m";
 pub const ANSI_RESET: &str = "\x1b[0m";
 
 pub fn read_extra_example_file(day: Day, number: u32) -> String {
-    let cwd = env::current_dir().unwrap();
-    let filepath = cwd
-        .join("data")
-        .join("examples")
+    let filepath = config::get()
+        .data
+        .examples
         .join(format!("{day}-{number}.txt"));
     let f = fs::read_to_string(filepath);
     f.expect("could not open input file")
 }
 
-/// Helper function that reads a text file to a string.
+/// Helper function that reads a text file to a string. `folder` is one of the
+/// data directories configured in `aoc.toml` (`inputs`, `examples`, ...).
 #[must_use]
 pub fn read_file(folder: &str, day: Day) -> String {
-    let cwd = env::current_dir().unwrap();
-    let filepath = cwd.join("data").join(folder).join(format!("{day}.txt"));
+    let filepath = config::get().data_dir(folder).join(format!("{day}.txt"));
     let f = fs::read_to_string(filepath);
     f.expect("could not open input file")
 }
 
+/// Reads the puzzle input for `day`, or the file passed with `--input <path>`
+/// if there is one (e.g. a generated stress test input).
+#[must_use]
+pub fn read_input(day: Day, options: &runner::RunOptions) -> String {
+    match &options.input {
+        Some(path) => fs::read_to_string(path).expect("could not open input file"),
+        None => read_file("inputs", day),
+    }
+}
+
 /// Creates the constant `DAY` and sets up the input and runner for each part.
 #[macro_export]
 macro_rules! solution {
@@ -38,9 +50,10 @@ macro_rules! solution {
 
         fn main() {
             use advent_of_code::template::runner::*;
-            let input = advent_of_code::template::read_file("inputs", DAY);
-            run_part(part_one, &input, DAY, 1);
-            run_part(part_two, &input, DAY, 2);
+            let options = RunOptions::from_env();
+            let input = advent_of_code::template::read_input(DAY, &options);
+            run_part(part_one, &input, DAY, 1, &options);
+            run_part(part_two, &input, DAY, 2, &options);
         }
     };
 }
@@ -0,0 +1,399 @@
+/// Terminal rendering of the puzzle descriptions that aoc-cli saves as markdown.
+///
+/// Only the subset of markdown that aoc-cli produces is understood: setext headings, paragraphs,
+/// list items, fenced code blocks and inline emphasis, strong, code and links.
+use crate::template::{ANSI_BOLD, ANSI_CODE, ANSI_ITALIC, ANSI_RESET};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Block {
+    Heading(String),
+    Paragraph(String),
+    ListItem(String),
+    Code(String),
+}
+
+/// A parsed puzzle description, split at the part headings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Puzzle {
+    pub parts: Vec<Vec<Block>>,
+}
+
+impl Puzzle {
+    pub fn parse(markdown: &str) -> Self {
+        let mut parts: Vec<Vec<Block>> = vec![];
+        let mut blocks: Vec<Block> = vec![];
+        let mut paragraph: Vec<&str> = vec![];
+        let mut code: Option<Vec<&str>> = None;
+
+        let flush = |paragraph: &mut Vec<&str>, blocks: &mut Vec<Block>| {
+            if paragraph.is_empty() {
+                return;
+            }
+            let text = paragraph.join(" ");
+            paragraph.clear();
+
+            match text.strip_prefix("* ").or_else(|| text.strip_prefix("- ")) {
+                Some(item) => blocks.push(Block::ListItem(item.to_string())),
+                None => blocks.push(Block::Paragraph(text)),
+            }
+        };
+
+        let mut lines = markdown.lines().peekable();
+
+        while let Some(line) = lines.next() {
+            if let Some(code_lines) = code.as_mut() {
+                if line.trim_start().starts_with("```") {
+                    blocks.push(Block::Code(code_lines.join("\n")));
+                    code = None;
+                } else {
+                    code_lines.push(line);
+                }
+                continue;
+            }
+
+            if line.trim_start().starts_with("```") {
+                flush(&mut paragraph, &mut blocks);
+                code = Some(vec![]);
+                continue;
+            }
+
+            let is_underline = |l: &&str| {
+                let l = l.trim();
+                !l.is_empty() && (l.chars().all(|c| c == '-') || l.chars().all(|c| c == '='))
+            };
+
+            let heading = if let Some(title) = line.strip_prefix('#') {
+                Some(title.trim_start_matches('#').trim().to_string())
+            } else if !line.trim().is_empty() && lines.peek().is_some_and(is_underline) {
+                lines.next();
+                Some(line.trim().to_string())
+            } else {
+                None
+            };
+
+            if let Some(heading) = heading {
+                flush(&mut paragraph, &mut blocks);
+                let heading = unescape(&heading);
+
+                if heading.contains("--- Part Two ---") && !blocks.is_empty() {
+                    parts.push(std::mem::take(&mut blocks));
+                }
+
+                blocks.push(Block::Heading(heading));
+                continue;
+            }
+
+            let is_item = line.starts_with("* ") || line.starts_with("- ");
+
+            if line.trim().is_empty() || is_item {
+                flush(&mut paragraph, &mut blocks);
+            }
+
+            if !line.trim().is_empty() {
+                paragraph.push(line.trim());
+            }
+        }
+
+        flush(&mut paragraph, &mut blocks);
+
+        if let Some(code_lines) = code {
+            blocks.push(Block::Code(code_lines.join("\n")));
+        }
+
+        if !blocks.is_empty() {
+            parts.push(blocks);
+        }
+
+        Self { parts }
+    }
+
+    /// All code blocks in order of appearance. These are the candidates for example inputs.
+    pub fn code_blocks(&self) -> impl Iterator<Item = &'_ str> + '_ {
+        self.parts.iter().flatten().filter_map(|block| match block {
+            Block::Code(code) => Some(code.as_str()),
+            _ => None,
+        })
+    }
+
+    /// Render `part` (1-based), or all parts if `None`, wrapped at `width` columns.
+    pub fn render(&self, part: Option<usize>, width: usize) -> String {
+        let mut out = vec![];
+        let mut example = 0;
+
+        for (i, blocks) in self.parts.iter().enumerate() {
+            let selected = part.is_none_or(|p| p == i + 1);
+
+            for block in blocks {
+                if let Block::Code(_) = block {
+                    example += 1;
+                }
+
+                if !selected {
+                    continue;
+                }
+
+                match block {
+                    Block::Heading(text) => {
+                        out.push(format!("{ANSI_BOLD}{}{ANSI_RESET}", unescape(text)));
+                    }
+                    Block::Paragraph(text) => out.extend(wrap(&styled_chars(text), width, "")),
+                    Block::ListItem(text) => {
+                        let mut lines = wrap(&styled_chars(text), width.saturating_sub(2), "  ");
+                        if let Some(first) = lines.first_mut() {
+                            first.replace_range(0..2, "• ");
+                        }
+                        out.extend(lines);
+                    }
+                    Block::Code(code) => {
+                        out.push(format!("{ANSI_ITALIC}┌ example {example}{ANSI_RESET}"));
+                        code.lines().for_each(|line| {
+                            out.push(format!(
+                                "{ANSI_ITALIC}│{ANSI_RESET} {ANSI_CODE}{line}{ANSI_RESET}"
+                            ))
+                        });
+                        out.push(format!("{ANSI_ITALIC}└{ANSI_RESET}"));
+                    }
+                }
+
+                out.push(String::new());
+            }
+        }
+
+        out.join("\n")
+    }
+}
+
+/// Strip the backslash escapes aoc-cli puts in front of markdown syntax characters
+fn unescape(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => result.extend(chars.next()),
+            c => result.push(c),
+        }
+    }
+
+    result
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Style {
+    bold: bool,
+    italic: bool,
+    code: bool,
+}
+
+impl Style {
+    fn ansi(self) -> String {
+        let mut result = String::new();
+        if self.bold {
+            result.push_str(ANSI_BOLD);
+        }
+        if self.italic {
+            result.push_str(ANSI_ITALIC);
+        }
+        if self.code {
+            result.push_str(ANSI_CODE);
+        }
+        result
+    }
+}
+
+/// Resolve inline markdown into characters with their style
+fn styled_chars(text: &str) -> Vec<(char, Style)> {
+    let chars = text.chars().collect::<Vec<_>>();
+    let mut result = vec![];
+    let mut style = Style::default();
+    let mut i = 0;
+
+    while i < chars.len() {
+        match chars[i] {
+            '\\' if i + 1 < chars.len() => {
+                result.push((chars[i + 1], style));
+                i += 2;
+                continue;
+            }
+            '`' => style.code = !style.code,
+            '*' if chars.get(i + 1) == Some(&'*') => {
+                style.bold = !style.bold;
+                i += 1;
+            }
+            // inside code, only treat asterisks as emphasis when they wrap the whole span, which is
+            // how aoc-cli renders emphasized code.
+            '*' if style.code => {
+                let closes = chars.get(i + 1) == Some(&'`');
+                let opens = i > 0 && chars[i - 1] == '`';
+                if opens || closes {
+                    style.italic = !style.italic;
+                } else {
+                    result.push(('*', style));
+                }
+            }
+            '*' => style.italic = !style.italic,
+            '[' => {
+                // links render as their text.
+                let close = chars[i..].iter().position(|c| *c == ']').map(|p| p + i);
+                let is_link = close.is_some_and(|c| chars.get(c + 1) == Some(&'('));
+                if let (true, Some(close)) = (is_link, close) {
+                    let end = chars[close..]
+                        .iter()
+                        .position(|c| *c == ')')
+                        .map(|p| p + close);
+                    if let Some(end) = end {
+                        let inner = chars[i + 1..close].iter().collect::<String>();
+                        result.extend(styled_chars(&inner).into_iter().map(|(c, s)| {
+                            (
+                                c,
+                                Style {
+                                    bold: s.bold || style.bold,
+                                    italic: s.italic || style.italic,
+                                    code: s.code || style.code,
+                                },
+                            )
+                        }));
+                        i = end + 1;
+                        continue;
+                    }
+                }
+                result.push(('[', style));
+            }
+            c => result.push((c, style)),
+        }
+        i += 1;
+    }
+
+    result
+}
+
+/// Greedily wrap styled characters into lines of at most `width` visible columns. Words longer
+/// than the width get a line of their own.
+fn wrap(chars: &[(char, Style)], width: usize, indent: &str) -> Vec<String> {
+    let words = chars
+        .split(|(c, _)| c.is_whitespace())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>();
+
+    let width = width.max(1);
+    let mut lines = vec![];
+    let mut line: Vec<&[(char, Style)]> = vec![];
+    let mut line_len = 0;
+
+    for word in words {
+        let sep = usize::from(!line.is_empty());
+        if !line.is_empty() && line_len + sep + word.len() > width {
+            lines.push(render_line(&line, indent));
+            line.clear();
+            line_len = 0;
+        }
+
+        line_len += usize::from(!line.is_empty()) + word.len();
+        line.push(word);
+    }
+
+    if !line.is_empty() {
+        lines.push(render_line(&line, indent));
+    }
+
+    lines
+}
+
+fn render_line(words: &[&[(char, Style)]], indent: &str) -> String {
+    let mut result = indent.to_string();
+    let mut current = Style::default();
+
+    for (i, word) in words.iter().enumerate() {
+        if i > 0 {
+            result.push(' ');
+        }
+
+        for &(c, style) in word.iter() {
+            if style != current {
+                result.push_str(ANSI_RESET);
+                result.push_str(&style.ansi());
+                current = style;
+            }
+            result.push(c);
+        }
+
+        // keep the spaces between words unstyled.
+        if current != Style::default() {
+            result.push_str(ANSI_RESET);
+            current = Style::default();
+        }
+    }
+
+    result
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{wrap, Block, Puzzle, Style};
+    use crate::template::{ANSI_BOLD, ANSI_RESET};
+
+    const PUZZLE: &str = r"\--- Day 1: Historian Hysteria ---
+----------
+
+The *Chief Historian* is always present.
+
+For example:
+
+```
+3   4
+4   3
+```
+
+* The first item
+* The second item
+
+Your puzzle answer was `1234`.
+
+\--- Part Two ---
+----------
+
+Count **each** number:
+
+```
+1
+```
+";
+
+    #[test]
+    fn parses_parts_and_blocks() {
+        let puzzle = Puzzle::parse(PUZZLE);
+        assert_eq!(puzzle.parts.len(), 2);
+        assert_eq!(
+            puzzle.parts[0][0],
+            Block::Heading("--- Day 1: Historian Hysteria ---".into())
+        );
+        assert_eq!(puzzle.parts[0][4], Block::ListItem("The first item".into()));
+        assert_eq!(
+            puzzle.code_blocks().collect::<Vec<_>>(),
+            vec!["3   4\n4   3", "1"]
+        );
+    }
+
+    #[test]
+    fn locked_part_two() {
+        let (part_one, _) = PUZZLE.split_once(r"\--- Part Two").unwrap();
+        assert_eq!(Puzzle::parse(part_one).parts.len(), 1);
+    }
+
+    #[test]
+    fn renders_single_part_with_example_numbers() {
+        let rendered = Puzzle::parse(PUZZLE).render(Some(2), 80);
+        assert!(!rendered.contains("Historian"));
+        assert!(rendered.contains("example 2"));
+        assert!(rendered.contains(&format!("{ANSI_RESET}{ANSI_BOLD}each{ANSI_RESET}")));
+    }
+
+    #[test]
+    fn wraps_at_visible_width() {
+        let chars = "aaa bbb ccc dd"
+            .chars()
+            .map(|c| (c, Style::default()))
+            .collect::<Vec<_>>();
+        assert_eq!(wrap(&chars, 7, ""), vec!["aaa bbb", "ccc dd"]);
+    }
+}
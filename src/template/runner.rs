@@ -1,73 +1,235 @@
 /// Encapsulates code that interacts with solution functions.
+use crate::template::config::{self, SubmitPolicy};
 use crate::template::{aoc_cli, ANSI_ITALIC, ANSI_RESET};
-use crate::Day;
+use crate::{memo, rng, visualize, Day};
 use std::fmt::Display;
 use std::io::{stdout, Write};
+use std::path::PathBuf;
 use std::process::Output;
+use std::sync::mpsc;
 use std::time::{Duration, Instant};
-use std::{cmp, env, process};
+use std::{cmp, process, thread};
+
+use clap::{Args, Parser};
 
 use super::ANSI_BOLD;
 
+/// Options understood by every solution binary. `cargo solve` forwards these
+/// to the binary it runs.
+#[derive(Debug, Clone, Default, Args)]
+pub struct RunOptions {
+    /// Benchmark each part instead of running it once.
+    #[arg(long)]
+    pub time: bool,
+    /// Submit the result of the given part via aoc-cli.
+    #[arg(long, value_name = "PART", value_parser = clap::value_parser!(u8).range(1..=2))]
+    pub submit: Option<u8>,
+    /// Read the puzzle input from this file instead of `data/inputs`.
+    #[arg(long, value_name = "PATH")]
+    pub input: Option<PathBuf>,
+    /// Abort a part that runs longer than this many seconds. Overrides
+    /// `timeout` in `aoc.toml`.
+    #[arg(long, value_name = "SECS")]
+    pub timeout: Option<u64>,
+    /// Upper bound of bench samples per part. Overrides `bench.max_samples`
+    /// in `aoc.toml`.
+    #[arg(long, value_name = "N")]
+    pub max_samples: Option<u64>,
+    /// Seed for solutions that use `advent_of_code::rng`. A random seed is
+    /// picked and logged if not given.
+    #[arg(long)]
+    pub seed: Option<u64>,
+    /// Print the cache statistics of `advent_of_code::memo` after each part.
+    #[arg(long)]
+    pub profile: bool,
+    /// Save the frames recorded with `advent_of_code::visualize` as a GIF if
+    /// the path ends in `.gif`, or as numbered PNGs in that directory.
+    #[arg(long, value_name = "PATH", conflicts_with = "time")]
+    pub record: Option<PathBuf>,
+}
+
+/// Run both parts of the solution for a single day.
+#[derive(Parser)]
+struct SolutionArguments {
+    #[command(flatten)]
+    options: RunOptions,
+}
+
+impl RunOptions {
+    /// Parse the options from the arguments of the current process and set
+    /// up the seed for [`crate::rng`].
+    #[must_use]
+    pub fn from_env() -> Self {
+        let mut options = SolutionArguments::parse().options;
+        let seed = *options.seed.get_or_insert_with(rand::random);
+        rng::set_seed(seed);
+        memo::set_profiling(options.profile);
+        if let Some(path) = &options.record {
+            visualize::set_output(path.clone());
+        }
+        options
+    }
+
+    /// Convert the options back into arguments for a child process.
+    #[must_use]
+    pub fn to_args(&self) -> Vec<String> {
+        let mut args = vec![];
+
+        if let Some(part) = self.submit {
+            args.push("--submit".to_string());
+            args.push(part.to_string());
+        }
+
+        if self.time {
+            args.push("--time".to_string());
+        }
+
+        if let Some(input) = &self.input {
+            args.push("--input".to_string());
+            args.push(input.to_string_lossy().into_owned());
+        }
+
+        if let Some(timeout) = self.timeout {
+            args.push("--timeout".to_string());
+            args.push(timeout.to_string());
+        }
+
+        if let Some(max_samples) = self.max_samples {
+            args.push("--max-samples".to_string());
+            args.push(max_samples.to_string());
+        }
+
+        if let Some(seed) = self.seed {
+            args.push("--seed".to_string());
+            args.push(seed.to_string());
+        }
+
+        if self.profile {
+            args.push("--profile".to_string());
+        }
+
+        if let Some(record) = &self.record {
+            args.push("--record".to_string());
+            args.push(record.to_string_lossy().into_owned());
+        }
+
+        args
+    }
+}
+
 pub fn run_part<I: Clone, T: Display>(
     func: impl Fn(I) -> Option<T>,
     input: I,
     day: Day,
     part: u8,
+    options: &RunOptions,
 ) {
     let part_str = format!("Part {part}");
+    let timeout = options
+        .timeout
+        .map(Duration::from_secs)
+        .or_else(|| config::get().timeout());
 
-    let (result, duration, samples) =
-        run_timed(func, input, |result| print_result(result, &part_str, ""));
+    let (result, duration, samples, stats) = run_timed(
+        func,
+        input,
+        options,
+        |result| print_result(result, &part_str, ""),
+        || abort_after(timeout, &part_str),
+    );
 
     print_result(&result, &part_str, &format_duration(&duration, samples));
 
+    if rng::take_used() {
+        let seed = rng::seed();
+        println!("{ANSI_ITALIC}  seed {seed} (replay with --seed {seed}){ANSI_RESET}");
+    }
+
+    if let Some(stats) = stats {
+        println!("{ANSI_ITALIC}  memo: {stats}{ANSI_RESET}");
+    }
+
+    if let (true, Some(path)) = (visualize::take_saved(), visualize::output()) {
+        println!("{ANSI_ITALIC}  frames saved to {}{ANSI_RESET}", path.display());
+    }
+
     if let Some(result) = result {
-        submit_result(result, day, part);
+        if options.submit == Some(part) {
+            if let Err(e) = submit_result(result, day, part) {
+                eprintln!("failed to call aoc-cli: {e}");
+            }
+        }
     }
 }
 
-/// Run a solution part. The behavior differs depending on whether we are running a release or debug build:
-///  1. in debug, the function is executed once.
-///  2. in release, the function is benched (approx. 1 second of execution time or 10 samples, whatever take longer.)
-fn run_timed<I: Clone, T>(
+/// Run a solution part. The behavior differs depending on whether the `--time` flag is set:
+///  1. without it, the function is executed once.
+///  2. with it, the function is benched (approx. the configured budget of execution time or the
+///     minimum number of samples, whatever take longer.)
+///
+/// `watchdog` is held for the duration of the first execution, and the returned memo stats
+/// describe that execution only.
+fn run_timed<I: Clone, T, G>(
     func: impl Fn(I) -> T,
     input: I,
+    options: &RunOptions,
     hook: impl Fn(&T),
-) -> (T, Duration, u128) {
+    watchdog: impl FnOnce() -> G,
+) -> (T, Duration, u128, Option<memo::Stats>) {
+    let guard = watchdog();
     let timer = Instant::now();
     let result = func(input.clone());
     let base_time = timer.elapsed();
+    drop(guard);
 
     hook(&result);
+    let stats = memo::take_stats();
 
-    let run = if std::env::args().any(|x| x == "--time") {
-        bench(func, input, &base_time)
+    let run = if options.time {
+        let run = bench(func, input, &base_time, options.max_samples);
+        memo::take_stats();
+        run
     } else {
         (base_time, 1)
     };
 
-    (result, run.0, run.1)
+    (result, run.0, run.1, stats)
+}
+
+/// Exit the process if the returned sender is not dropped within `timeout`.
+fn abort_after(timeout: Option<Duration>, part: &str) -> Option<mpsc::Sender<()>> {
+    let timeout = timeout?;
+    let (sender, receiver) = mpsc::channel::<()>();
+    let part = part.to_string();
+
+    thread::spawn(move || {
+        if let Err(mpsc::RecvTimeoutError::Timeout) = receiver.recv_timeout(timeout) {
+            println!("\r{part}: ✖ (timed out after {timeout:.1?})");
+            process::exit(1);
+        }
+    });
+
+    Some(sender)
 }
 
 fn bench<I: Clone, T>(
     func: impl Fn(I) -> T,
     input: I,
     base_time: &Duration,
+    max_samples: Option<u64>,
 ) -> (Duration, u128) {
     let mut stdout = stdout();
 
     print!(" > {ANSI_ITALIC}benching{ANSI_RESET}");
     let _ = stdout.flush();
 
-    let bench_iterations = cmp::min(
-        10000,
-        cmp::max(
-            Duration::from_secs(1).as_nanos()
-                / cmp::max(base_time.as_nanos(), 10),
-            10,
-        ),
-    );
+    let config = &config::get().bench;
+    let max_samples = u128::from(max_samples.unwrap_or(config.max_samples).max(1));
+    let min_samples = u128::from(config.min_samples).min(max_samples);
+
+    let bench_iterations = (Duration::from_millis(config.budget_ms).as_nanos()
+        / cmp::max(base_time.as_nanos(), 10))
+    .clamp(min_samples, max_samples);
 
     let mut timers: Vec<Duration> = vec![];
 
@@ -143,38 +305,24 @@ fn print_result<T: Display>(
     }
 }
 
-/// Parse the arguments passed to `solve` and try to submit one part of the solution if:
-///  1. we are in `--release` mode.
-///  2. aoc-cli is installed.
+/// Submit one part of the solution, exiting if aoc-cli is not installed or the
+/// configured submission policy forbids it.
 fn submit_result<T: Display>(
     result: T,
     day: Day,
     part: u8,
-) -> Option<Result<Output, aoc_cli::AocCommandError>> {
-    let args: Vec<String> = env::args().collect();
-
-    if !args.contains(&"--submit".into()) {
-        return None;
-    }
-
-    if args.len() < 3 {
-        eprintln!(
-            "Unexpected command-line input. Format: cargo solve 1 --submit 1"
-        );
-        process::exit(1);
-    }
-
-    let part_index = args.iter().position(|x| x == "--submit").unwrap() + 1;
-
-    let Ok(part_submit) = args[part_index].parse::<u8>() else {
-        eprintln!(
-            "Unexpected command-line input. Format: cargo solve 1 --submit 1"
-        );
-        process::exit(1);
-    };
-
-    if part_submit != part {
-        return None;
+) -> Result<Output, aoc_cli::AocCommandError> {
+    match config::get().submit.policy {
+        SubmitPolicy::Always => {}
+        SubmitPolicy::ReleaseOnly if !cfg!(debug_assertions) => {}
+        SubmitPolicy::ReleaseOnly => {
+            eprintln!("Submission policy is \"release-only\". Run with --release to submit.");
+            process::exit(1);
+        }
+        SubmitPolicy::Never => {
+            eprintln!("Submission policy is \"never\". Change it in aoc.toml to submit.");
+            process::exit(1);
+        }
     }
 
     if aoc_cli::check().is_err() {
@@ -183,5 +331,5 @@ fn submit_result<T: Display>(
     }
 
     println!("Submitting result via aoc-cli...");
-    Some(aoc_cli::submit(day, part, &result.to_string()))
+    aoc_cli::submit(day, part, &result.to_string())
 }
@@ -0,0 +1,209 @@
+//! Whole-grid transformations. All of them return a new grid with `width`,
+//! `height` and `data` consistent with each other.
+
+use crate::{Grid, Pos, Topology};
+
+impl<T> Grid<T> {
+    /// Build a grid by calling `f` with the column and row of every cell
+    pub fn from_fn<F>(width: usize, height: usize, mut f: F) -> Self
+    where
+        F: FnMut(usize, usize) -> T,
+    {
+        let data = (0..height)
+            .flat_map(|row| (0..width).map(move |col| (col, row)))
+            .map(|(col, row)| f(col, row))
+            .collect();
+
+        Self::new(data, width)
+    }
+
+    /// The cells of column `col` from top to bottom. Panics if `col` is off
+    /// the grid.
+    pub fn column(&self, col: usize) -> impl DoubleEndedIterator<Item = &'_ T> {
+        assert!(col < self.width, "column {col} is off the grid");
+        self.data[col..].iter().step_by(self.width)
+    }
+
+    pub fn columns(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = impl DoubleEndedIterator<Item = &'_ T>>
+    {
+        (0..self.width).map(|col| self.column(col))
+    }
+
+    /// Replace every cell with the `k` cells `expand` produces for it, making
+    /// the grid `k` times as wide
+    pub fn scale_x<F, I, U>(self, k: usize, expand: F) -> Grid<U>
+    where
+        F: FnMut(T) -> I,
+        I: IntoIterator<Item = U>,
+    {
+        let width = self.width * k;
+        let topology = self.topology;
+        let data: Vec<U> = self.data.into_iter().flat_map(expand).collect();
+
+        assert_eq!(
+            data.len(),
+            width * self.height,
+            "expand must yield k cells"
+        );
+
+        Grid::new(data, width).with_topology(topology)
+    }
+}
+
+impl<T> Grid<T>
+where
+    T: Clone,
+{
+    fn remapped<F>(&self, width: usize, height: usize, swap: bool, f: F) -> Self
+    where
+        F: Fn(usize, usize) -> (usize, usize),
+    {
+        let topology = if swap {
+            Topology {
+                wrap_x: self.topology.wrap_y,
+                wrap_y: self.topology.wrap_x,
+            }
+        } else {
+            self.topology
+        };
+
+        Self::from_fn(width, height, |col, row| {
+            let (col, row) = f(col, row);
+            self.data[self.to_index(col, row)].clone()
+        })
+        .with_topology(topology)
+    }
+
+    /// Rotate by 90° clockwise
+    pub fn rotate_right(&self) -> Self {
+        let h = self.height;
+        self.remapped(self.height, self.width, true, |c, r| (r, h - 1 - c))
+    }
+
+    /// Rotate by 90° counter-clockwise
+    pub fn rotate_left(&self) -> Self {
+        let w = self.width;
+        self.remapped(self.height, self.width, true, |c, r| (w - 1 - r, c))
+    }
+
+    pub fn rotate_180(&self) -> Self {
+        let (w, h) = (self.width, self.height);
+        self.remapped(w, h, false, |c, r| (w - 1 - c, h - 1 - r))
+    }
+
+    /// Mirror left to right
+    pub fn flip_horizontal(&self) -> Self {
+        let w = self.width;
+        self.remapped(w, self.height, false, |c, r| (w - 1 - c, r))
+    }
+
+    /// Mirror top to bottom
+    pub fn flip_vertical(&self) -> Self {
+        let h = self.height;
+        self.remapped(self.width, h, false, |c, r| (c, h - 1 - r))
+    }
+
+    /// Mirror along the top left to bottom right diagonal
+    pub fn transpose(&self) -> Self {
+        self.remapped(self.height, self.width, true, |c, r| (r, c))
+    }
+
+    /// Copy out the cells between `min` and `max` (both inclusive), or `None`
+    /// if they are not both on the grid. The result is always bounded.
+    pub fn subgrid(&self, (min, max): (Pos, Pos)) -> Option<Self> {
+        let (x0, y0) = (min.x as usize, min.y as usize);
+        let valid = self.contains(min) && self.contains(max);
+
+        (valid && min.x <= max.x && min.y <= max.y).then(|| {
+            let width = (max.x - min.x + 1) as usize;
+            let height = (max.y - min.y + 1) as usize;
+
+            Self::from_fn(width, height, |c, r| {
+                self.data[self.to_index(x0 + c, y0 + r)].clone()
+            })
+        })
+    }
+
+    /// Repeat the grid `nx` times horizontally and `ny` times vertically.
+    /// Both have to be at least 1.
+    pub fn tile(&self, nx: usize, ny: usize) -> Self {
+        assert!(nx > 0 && ny > 0, "a grid must be tiled at least once");
+        let (w, h) = (self.width, self.height);
+        self.remapped(w * nx, h * ny, false, |c, r| (c % w, r % h))
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use crate::{Grid, Pos};
+
+    fn grid() -> Grid<char> {
+        Grid::parse_lines("abc\ndef")
+    }
+
+    fn lines(grid: &Grid<char>) -> Vec<String> {
+        grid.rows().map(|row| row.iter().collect()).collect()
+    }
+
+    #[test]
+    fn rotations() {
+        let g = grid();
+        assert_eq!(lines(&g.rotate_right()), ["da", "eb", "fc"]);
+        assert_eq!(lines(&g.rotate_left()), ["cf", "be", "ad"]);
+        assert_eq!(lines(&g.rotate_180()), ["fed", "cba"]);
+        assert_eq!(g.rotate_right().rotate_left(), g);
+        assert_eq!(g.rotate_right().rotate_right(), g.rotate_180());
+    }
+
+    #[test]
+    fn flips() {
+        let g = grid();
+        assert_eq!(lines(&g.flip_horizontal()), ["cba", "fed"]);
+        assert_eq!(lines(&g.flip_vertical()), ["def", "abc"]);
+        assert_eq!(lines(&g.transpose()), ["ad", "be", "cf"]);
+        assert_eq!(g.transpose().height, 3);
+    }
+
+    #[test]
+    fn subgrid_and_tiling() {
+        let g = grid();
+        let sub = g.subgrid((Pos::new(1, 0), Pos::new(2, 1))).unwrap();
+        assert_eq!(lines(&sub), ["bc", "ef"]);
+        assert!(g.subgrid((Pos::new(1, 0), Pos::new(3, 1))).is_none());
+
+        assert_eq!(
+            lines(&g.tile(2, 2)),
+            ["abcabc", "defdef", "abcabc", "defdef"]
+        );
+        assert_eq!(g.clone().quadruple(), g.tile(2, 2));
+
+        let wide = g.scale_x(2, |c| [c, c.to_ascii_uppercase()]);
+        assert_eq!(lines(&wide), ["aAbBcC", "dDeEfF"]);
+    }
+
+    #[test]
+    fn columns() {
+        let g = grid();
+        let cols = g
+            .columns()
+            .map(|col| col.collect::<String>())
+            .collect::<Vec<_>>();
+        assert_eq!(cols, ["ad", "be", "cf"]);
+        assert_eq!(g.column(2).rev().collect::<String>(), "fc");
+    }
+
+    #[test]
+    #[should_panic(expected = "off the grid")]
+    fn column_off_grid() {
+        // index 3 is in `data`, but it's the first cell of the second row
+        grid().column(3);
+    }
+
+    #[test]
+    #[should_panic(expected = "tiled at least once")]
+    fn empty_tiling() {
+        grid().tile(0, 2);
+    }
+}
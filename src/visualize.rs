@@ -0,0 +1,360 @@
+//! Record grid snapshots while a solution runs and export them as an animated
+//! GIF or a sequence of PNG images.
+//!
+//! Solutions take a `&mut impl FrameSink<T>` and push the grid after each
+//! step. Passing `&mut ()` records nothing, so the normal run stays fast.
+//! Solutions that have to build a grid just to push it can skip that when
+//! [`FrameSink::is_recording`] is false.
+//!
+//! With `--record <path>` the runner sets the [`output`] of the run, and
+//! solutions wrapped in [`record`] save their frames there.
+
+use std::{
+    collections::HashMap,
+    fmt, fs,
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+use once_cell::sync::OnceCell;
+
+use crate::Grid;
+
+static OUTPUT: OnceCell<PathBuf> = OnceCell::new();
+static SAVED: AtomicBool = AtomicBool::new(false);
+
+/// Save the recordings of this process to `path`, see [`Recorder::save`].
+/// Returns false if an output was already set.
+pub fn set_output(path: PathBuf) -> bool {
+    OUTPUT.set(path).is_ok()
+}
+
+/// Where the recordings of this process are saved, if anywhere
+pub fn output() -> Option<&'static Path> {
+    OUTPUT.get().map(PathBuf::as_path)
+}
+
+/// Check if a recording was saved since the last call, resetting the flag.
+pub fn take_saved() -> bool {
+    SAVED.swap(false, Ordering::Relaxed)
+}
+
+/// Run `f` with a recorder made by `recorder` and save its frames to the
+/// [`output`], if there is one. Otherwise `f` gets `None` and nothing is
+/// recorded.
+pub fn record<T, R>(
+    recorder: impl FnOnce() -> Recorder<T>,
+    f: impl FnOnce(&mut Option<Recorder<T>>) -> R,
+) -> Result<R, Error> {
+    let mut frames = output().map(|_| recorder());
+    let result = f(&mut frames);
+
+    if let (Some(frames), Some(path)) = (frames, output()) {
+        frames.save(path)?;
+        SAVED.store(true, Ordering::Relaxed);
+    }
+
+    Ok(result)
+}
+
+pub type Rgb = [u8; 3];
+
+/// Something grid snapshots can be pushed to
+pub trait FrameSink<T> {
+    fn push(&mut self, grid: &Grid<T>);
+
+    /// Whether pushed frames are kept at all
+    fn is_recording(&self) -> bool {
+        true
+    }
+}
+
+/// Ignores all frames
+impl<T> FrameSink<T> for () {
+    fn push(&mut self, _: &Grid<T>) {}
+
+    fn is_recording(&self) -> bool {
+        false
+    }
+}
+
+#[derive(Debug)]
+pub enum Error {
+    IO(PathBuf, io::Error),
+    Gif(gif::EncodingError),
+    Png(png::EncodingError),
+    /// Frames of this many pixels don't fit in a GIF
+    TooLarge(usize, usize),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::IO(path, e) => {
+                write!(f, "could not write {}: {e}", path.display())
+            }
+            Error::Gif(e) => write!(f, "could not encode gif: {e}"),
+            Error::Png(e) => write!(f, "could not encode png: {e}"),
+            Error::TooLarge(width, height) => {
+                write!(
+                    f,
+                    "{width}x{height} pixel frames are too large for a gif"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Collects frames as palette indexed images, one pixel block per cell
+pub struct Recorder<T> {
+    color: Box<dyn Fn(&T) -> Rgb>,
+    palette: Vec<Rgb>,
+    lookup: HashMap<Rgb, u8>,
+    size: Option<(usize, usize)>,
+    frames: Vec<Vec<u8>>,
+    scale: usize,
+    delay: Duration,
+    every: usize,
+    pushed: usize,
+}
+
+impl<T> Recorder<T> {
+    /// Record frames, coloring each cell with `color`. At most 256 distinct
+    /// colors can be used.
+    pub fn new<F>(color: F) -> Self
+    where
+        F: Fn(&T) -> Rgb + 'static,
+    {
+        Self {
+            color: Box::new(color),
+            palette: vec![],
+            lookup: HashMap::new(),
+            size: None,
+            frames: vec![],
+            scale: 4,
+            delay: Duration::from_millis(50),
+            every: 1,
+            pushed: 0,
+        }
+    }
+
+    /// Side length of a cell in pixels. Defaults to 4.
+    pub fn scale(self, scale: usize) -> Self {
+        Self { scale, ..self }
+    }
+
+    /// Time each frame is shown in the GIF. Defaults to 50ms.
+    pub fn delay(self, delay: Duration) -> Self {
+        Self { delay, ..self }
+    }
+
+    /// Only keep every `n`th pushed frame, for runs with many steps
+    pub fn every(self, n: usize) -> Self {
+        Self { every: n, ..self }
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Width and height of the frames in pixels
+    fn pixel_size(&self) -> (usize, usize) {
+        let (width, height) = self.size.unwrap_or_default();
+        (width * self.scale, height * self.scale)
+    }
+
+    /// Scale a frame up to one block of pixels per cell
+    fn pixels(&self, frame: &[u8]) -> Vec<u8> {
+        let (width, _) = self.size.unwrap_or_default();
+
+        frame
+            .chunks(width)
+            .flat_map(|row| {
+                let line = row
+                    .iter()
+                    .flat_map(|c| std::iter::repeat_n(*c, self.scale))
+                    .collect::<Vec<_>>();
+                std::iter::repeat_n(line, self.scale).flatten()
+            })
+            .collect()
+    }
+
+    fn flat_palette(&self) -> Vec<u8> {
+        self.palette.iter().flatten().copied().collect()
+    }
+
+    pub fn write_gif<W: Write>(&self, out: W) -> Result<(), Error> {
+        let (width, height) = self.pixel_size();
+        let (Ok(gif_width), Ok(gif_height)) =
+            (u16::try_from(width), u16::try_from(height))
+        else {
+            return Err(Error::TooLarge(width, height));
+        };
+
+        let mut encoder =
+            gif::Encoder::new(out, gif_width, gif_height, &self.flat_palette())
+                .map_err(Error::Gif)?;
+        encoder
+            .set_repeat(gif::Repeat::Infinite)
+            .map_err(Error::Gif)?;
+
+        let delay = (self.delay.as_millis() / 10) as u16;
+
+        for frame in &self.frames {
+            let mut frame = gif::Frame::from_indexed_pixels(
+                gif_width,
+                gif_height,
+                self.pixels(frame),
+                None,
+            );
+            frame.delay = delay;
+            encoder.write_frame(&frame).map_err(Error::Gif)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn write_png<W: Write>(
+        &self,
+        frame: usize,
+        out: W,
+    ) -> Result<(), Error> {
+        let (width, height) = self.pixel_size();
+        let mut encoder = png::Encoder::new(out, width as u32, height as u32);
+        encoder.set_color(png::ColorType::Indexed);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.set_palette(self.flat_palette());
+
+        let mut writer = encoder.write_header().map_err(Error::Png)?;
+        writer
+            .write_image_data(&self.pixels(&self.frames[frame]))
+            .map_err(Error::Png)
+    }
+
+    /// Save as an animated GIF if `path` ends in `.gif`. Otherwise `path` is
+    /// a directory that gets one numbered PNG per frame.
+    pub fn save(&self, path: &Path) -> Result<(), Error> {
+        let create = |path: &Path| {
+            fs::File::create(path)
+                .map(BufWriter::new)
+                .map_err(|e| Error::IO(path.to_path_buf(), e))
+        };
+
+        if path.extension().is_some_and(|ext| ext == "gif") {
+            return self.write_gif(create(path)?);
+        }
+
+        fs::create_dir_all(path)
+            .map_err(|e| Error::IO(path.to_path_buf(), e))?;
+
+        (0..self.frames.len()).try_for_each(|i| {
+            let file = path.join(format!("{i:05}.png"));
+            self.write_png(i, create(&file)?)
+        })
+    }
+}
+
+impl<T> FrameSink<T> for Recorder<T> {
+    fn push(&mut self, grid: &Grid<T>) {
+        self.pushed += 1;
+        if (self.pushed - 1) % self.every != 0 {
+            return;
+        }
+
+        let size = *self.size.get_or_insert((grid.width, grid.height));
+        assert_eq!(
+            size,
+            (grid.width, grid.height),
+            "frames must have the same size"
+        );
+
+        let frame = grid
+            .data
+            .iter()
+            .map(|t| {
+                let rgb = (self.color)(t);
+                *self.lookup.entry(rgb).or_insert_with(|| {
+                    assert!(self.palette.len() < 256, "at most 256 colors");
+                    self.palette.push(rgb);
+                    (self.palette.len() - 1) as u8
+                })
+            })
+            .collect();
+
+        self.frames.push(frame);
+    }
+}
+
+/// Records if there is a recorder
+impl<T> FrameSink<T> for Option<Recorder<T>> {
+    fn push(&mut self, grid: &Grid<T>) {
+        if let Some(recorder) = self {
+            recorder.push(grid);
+        }
+    }
+
+    fn is_recording(&self) -> bool {
+        self.is_some()
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::*;
+
+    fn recorder() -> Recorder<char> {
+        let mut recorder = Recorder::new(|c: &char| match c {
+            '#' => [255, 255, 255],
+            _ => [0, 0, 0],
+        })
+        .scale(2)
+        .every(2);
+
+        let mut grid: Grid<char> = Grid::parse_lines("#.\n..");
+        for i in 0..4 {
+            grid.data[i] = '#';
+            recorder.push(&grid);
+        }
+
+        recorder
+    }
+
+    #[test]
+    fn records_every_nth_frame() {
+        let recorder = recorder();
+        assert_eq!(recorder.len(), 2);
+        assert_eq!(recorder.palette.len(), 2);
+        assert_eq!(recorder.pixels(&recorder.frames[0])[..4], [0, 0, 1, 1]);
+    }
+
+    #[test]
+    fn encodes_gif_and_png() {
+        let recorder = recorder();
+
+        let mut gif = vec![];
+        recorder.write_gif(&mut gif).unwrap();
+        assert!(gif.starts_with(b"GIF89a"));
+
+        let mut png = vec![];
+        recorder.write_png(1, &mut png).unwrap();
+        assert!(png.starts_with(b"\x89PNG"));
+    }
+
+    #[test]
+    fn rejects_oversized_gif() {
+        let recorder = recorder().scale(40_000);
+
+        assert!(matches!(
+            recorder.write_gif(vec![]),
+            Err(Error::TooLarge(80_000, 80_000))
+        ));
+    }
+}